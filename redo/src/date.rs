@@ -0,0 +1,129 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// the most days a date can be from 1970-01-01, the year of the date still fits an `i32`
+const MAX_DAYS: i64 = i32::MAX as i64 * 365;
+
+/// A calendar day, written as `YYYY-MM-DD` in todo files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    pub year: i32,
+    pub month: u8,
+    pub day: u8,
+}
+
+impl Date {
+    pub fn new(year: i32, month: u8, day: u8) -> Option<Self> {
+        if !(1..=12).contains(&month) || day == 0 || day > days_in_month(year, month) {
+            return None;
+        }
+        Some(Self { year, month, day })
+    }
+
+    /// the current day in UTC
+    pub fn today() -> Self {
+        let secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|time| time.as_secs() as i64)
+            .unwrap_or_default();
+        Self::from_days(secs.div_euclid(86_400))
+    }
+
+    /// the day `days` days after 1970-01-01
+    pub fn from_days(days: i64) -> Self {
+        // http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+        let days = days + 719_468;
+        let era = days.div_euclid(146_097);
+        let doe = days.rem_euclid(146_097);
+        let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = (doy - (153 * mp + 2) / 5 + 1) as u8;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u8;
+        let year = (yoe + era * 400 + i64::from(month <= 2)) as i32;
+        Self { year, month, day }
+    }
+
+    /// the amount of days since 1970-01-01
+    pub fn days(&self) -> i64 {
+        // http://howardhinnant.github.io/date_algorithms.html#days_from_civil
+        let year = i64::from(self.year) - i64::from(self.month <= 2);
+        let era = year.div_euclid(400);
+        let yoe = year.rem_euclid(400);
+        let month = i64::from(self.month);
+        let doy = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + i64::from(self.day) - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+        era * 146_097 + doe - 719_468
+    }
+
+    /// the day `days` days later, as far as a date goes
+    pub fn add_days(&self, days: i64) -> Self {
+        Self::from_days(self.days().saturating_add(days).clamp(-MAX_DAYS, MAX_DAYS))
+    }
+
+    /// the day `days` days later, `None` when that is further than a date goes
+    pub fn checked_add_days(&self, days: i64) -> Option<Self> {
+        let days = self.days().checked_add(days)?;
+        (-MAX_DAYS..=MAX_DAYS).contains(&days).then(|| Self::from_days(days))
+    }
+
+    /// the day of the week, 0 is monday and 6 is sunday
+    pub fn weekday(&self) -> u8 {
+        // 1970-01-01 was a thursday
+        (self.days() + 3).rem_euclid(7) as u8
+    }
+}
+
+pub fn days_in_month(year: i32, month: u8) -> u8 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+impl std::fmt::Display for Date {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+impl std::str::FromStr for Date {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid date `{value}`, expected YYYY-MM-DD");
+        let mut parts = value.splitn(3, '-');
+        let mut next = || parts.next().ok_or_else(invalid);
+        let (year, month, day) = (next()?, next()?, next()?);
+        let year = year.parse().map_err(|_| invalid())?;
+        let month = month.parse().map_err(|_| invalid())?;
+        let day = day.parse().map_err(|_| invalid())?;
+        Self::new(year, month, day).ok_or_else(invalid)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Date;
+
+    #[test]
+    fn test_days() {
+        let date = Date::new(2024, 2, 29).expect("leap day");
+        assert_eq!(Date::from_days(date.days()), date);
+        assert_eq!(Date::from_days(0).to_string(), "1970-01-01");
+        assert_eq!(date.add_days(1).to_string(), "2024-03-01");
+        assert_eq!(date.add_days(-60).to_string(), "2023-12-31");
+        assert_eq!(date.weekday(), 3);
+        assert_eq!(date.checked_add_days(i64::MAX), None);
+        assert_eq!(date.checked_add_days(-1), Some(date.add_days(-1)));
+    }
+
+    #[test]
+    fn test_parse_date() {
+        assert_eq!("2024-10-05".parse(), Ok(Date::new(2024, 10, 5).expect("valid")));
+        assert!("2023-02-29".parse::<Date>().is_err());
+        assert!("2024-10".parse::<Date>().is_err());
+        assert!("tomorrow".parse::<Date>().is_err());
+    }
+}
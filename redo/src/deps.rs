@@ -0,0 +1,179 @@
+use std::collections::HashMap;
+
+use crate::todo::{Todo, TodoListCollection, TodoStatus};
+
+impl Todo {
+    /// the stable name other todos depend on this one by, from `id:name`
+    pub fn id(&self) -> Option<&str> {
+        self.meta("id")
+    }
+
+    /// the ids of the todos this one waits for, from `dep:name` words, which can list several as `dep:a,b`
+    pub fn dependencies(&self) -> impl Iterator<Item = &str> {
+        self.data
+            .split_whitespace()
+            .filter_map(|word| word.strip_prefix("dep:"))
+            .flat_map(|ids| ids.split(','))
+            .filter(|id| !id.is_empty())
+    }
+}
+
+/// The `id:` and `dep:` links between the todos of a collection.
+///
+/// A todo is blocked while one of the todos it depends on is still open, dependencies on ids no todo has do not
+/// block anything but are reported by [`Dependencies::validate`].
+#[derive(Debug)]
+pub struct Dependencies<'a> {
+    ids: HashMap<&'a str, &'a Todo>,
+    /// ids used by more than one todo, the first one of them is the one depended on
+    duplicates: Vec<&'a str>,
+    /// ids depended on that no todo has
+    unknown: Vec<&'a str>,
+}
+
+impl<'a> Dependencies<'a> {
+    pub fn new(collection: &'a TodoListCollection) -> Self {
+        let todos = || collection.lists.iter().flat_map(|list| &list.data);
+        let mut ids = HashMap::new();
+        let mut duplicates = vec![];
+        for todo in todos() {
+            let Some(id) = todo.id() else {
+                continue;
+            };
+            if ids.contains_key(id) {
+                duplicates.push(id);
+            } else {
+                ids.insert(id, todo);
+            }
+        }
+        let unknown = todos()
+            .flat_map(Todo::dependencies)
+            .filter(|id| !ids.contains_key(id))
+            .collect();
+        Self {
+            ids,
+            duplicates,
+            unknown,
+        }
+    }
+
+    /// the ids of the open todos `todo` waits for
+    pub fn blockers<'b>(&self, todo: &'b Todo) -> Vec<&'b str> {
+        todo.dependencies()
+            .filter(|id| {
+                self.ids
+                    .get(id)
+                    .is_some_and(|other| other.status == TodoStatus::Incomplete)
+            })
+            .collect()
+    }
+
+    pub fn is_blocked(&self, todo: &Todo) -> bool {
+        !self.blockers(todo).is_empty()
+    }
+
+    /// whether the todo can be worked on, it is open and nothing it depends on is
+    pub fn is_actionable(&self, todo: &Todo) -> bool {
+        todo.status == TodoStatus::Incomplete && !self.is_blocked(todo)
+    }
+
+    /// the first problem with the links, an id used twice, a dependency on an unknown id or a cycle
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(id) = self.duplicates.first() {
+            return Err(format!("more than one todo has `id:{id}`"));
+        }
+        if let Some(id) = self.unknown.first() {
+            return Err(format!("a todo depends on `{id}`, but no todo has `id:{id}`"));
+        }
+        let mut ids = self.ids.keys().copied().collect::<Vec<_>>();
+        // the same cycle is reported every time
+        ids.sort_unstable();
+        for id in ids {
+            if let Some(cycle) = self.cycle_from(id) {
+                return Err(format!("the dependencies {} form a cycle", cycle.join(" -> ")));
+            }
+        }
+        Ok(())
+    }
+
+    /// the ids on a path from `start` that leads back to it
+    fn cycle_from(&self, start: &'a str) -> Option<Vec<&'a str>> {
+        let mut path = vec![start];
+        let mut visited = vec![start];
+        self.find_path(start, start, &mut path, &mut visited).then_some(path)
+    }
+
+    fn find_path(&self, from: &'a str, to: &'a str, path: &mut Vec<&'a str>, visited: &mut Vec<&'a str>) -> bool {
+        let Some(todo) = self.ids.get(from) else {
+            return false;
+        };
+        for dep in todo.dependencies() {
+            let Some((dep, _)) = self.ids.get_key_value(dep) else {
+                continue;
+            };
+            if *dep == to {
+                path.push(dep);
+                return true;
+            }
+            if visited.contains(dep) {
+                continue;
+            }
+            visited.push(dep);
+            path.push(dep);
+            if self.find_path(dep, to, path, visited) {
+                return true;
+            }
+            path.pop();
+        }
+        false
+    }
+}
+
+impl TodoListCollection {
+    pub fn dependencies(&self) -> Dependencies<'_> {
+        Dependencies::new(self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::parser::parse_collection;
+
+    #[test]
+    fn test_blocked() {
+        let content = "[release]:
+        [ ] review id:review
+        [x] tests id:tests
+        [ ] deploy dep:review,tests
+        [ ] announce dep:deploy";
+        let collection = parse_collection(content).expect("valid collection");
+        let dependencies = collection.dependencies();
+        let todos = &collection.lists[0].data;
+
+        assert_eq!(dependencies.blockers(&todos[2]), vec!["review"]);
+        assert!(dependencies.is_actionable(&todos[0]));
+        assert!(!dependencies.is_actionable(&todos[1]));
+        assert!(!dependencies.is_blocked(&todos[3]));
+        assert_eq!(
+            dependencies.validate(),
+            Err("a todo depends on `deploy`, but no todo has `id:deploy`".to_string())
+        );
+    }
+
+    #[test]
+    fn test_validate() {
+        let valid = parse_collection("[a]:\n[ ] one id:one\n[ ] two id:two dep:one").expect("valid collection");
+        assert_eq!(valid.dependencies().validate(), Ok(()));
+
+        let cycle =
+            parse_collection("[a]:\n[ ] one id:one dep:three\n[ ] two id:two dep:one\n[ ] three id:three dep:two")
+                .expect("valid collection");
+        assert_eq!(
+            cycle.dependencies().validate(),
+            Err("the dependencies one -> three -> two -> one form a cycle".to_string())
+        );
+
+        let duplicate = parse_collection("[a]:\n[ ] one id:one\n[b]:\n[ ] other id:one").expect("valid collection");
+        assert!(duplicate.dependencies().validate().is_err());
+    }
+}
@@ -0,0 +1,134 @@
+use serde::Serialize;
+
+use crate::parser;
+use crate::todo::{TodoListCollection, TodoStatus};
+
+/// The formats a collection can be exported as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// the same format the todo files are written in
+    Todo,
+    Json,
+    Markdown,
+}
+
+impl Format {
+    pub const NAMES: [&'static str; 3] = ["todo", "json", "markdown"];
+}
+
+impl std::str::FromStr for Format {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "todo" => Ok(Format::Todo),
+            "json" => Ok(Format::Json),
+            "markdown" | "md" => Ok(Format::Markdown),
+            _ => Err(format!(
+                "unknown format `{name}`, expected one of {}",
+                Format::NAMES.join(", ")
+            )),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct JsonList<'a> {
+    title: &'a str,
+    todos: Vec<JsonTodo<'a>>,
+}
+
+#[derive(Serialize)]
+struct JsonTodo<'a> {
+    text: &'a str,
+    done: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    created: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    completed: Option<String>,
+    #[serde(skip_serializing_if = "str::is_empty")]
+    note: &'a str,
+}
+
+pub fn export(collection: &TodoListCollection, format: Format) -> Result<String, String> {
+    match format {
+        Format::Todo => Ok(parser::serialize(collection)),
+        Format::Json => {
+            let lists = collection
+                .lists
+                .iter()
+                .map(|list| JsonList {
+                    title: list.name(),
+                    todos: list
+                        .data
+                        .iter()
+                        .map(|todo| JsonTodo {
+                            text: &todo.data,
+                            done: todo.status == TodoStatus::Complete,
+                            created: todo.created.map(|date| date.to_string()),
+                            completed: todo.completed.map(|date| date.to_string()),
+                            note: &todo.note,
+                        })
+                        .collect(),
+                })
+                .collect::<Vec<_>>();
+            serde_json::to_string_pretty(&lists).map_err(|e| e.to_string())
+        }
+        Format::Markdown => {
+            let mut content = String::default();
+            for list in &collection.lists {
+                if !content.is_empty() {
+                    content.push('\n');
+                }
+                content.push_str(&format!("## {}\n\n", list.name()));
+                for todo in &list.data {
+                    let done = match todo.status {
+                        TodoStatus::Complete => 'x',
+                        TodoStatus::Incomplete => ' ',
+                    };
+                    content.push_str(&format!("- [{done}] {}\n", todo.data));
+                    // indented lines belong to the list item
+                    for line in todo.note.split('\n').filter(|_| !todo.note.is_empty()) {
+                        content.push_str(&format!("  {line}\n"));
+                    }
+                }
+            }
+            Ok(content)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{export, Format};
+    use crate::parser::parse_collection;
+
+    #[test]
+    fn test_export() {
+        let collection =
+            parse_collection("[work]:\n[ ] write \"report\"\n[x] email done:2024-10-01").expect("valid collection");
+
+        let json = export(&collection, Format::Json).expect("json export");
+        let expected = r#"[
+  {
+    "title": "work",
+    "todos": [
+      {
+        "text": "write \"report\"",
+        "done": false
+      },
+      {
+        "text": "email",
+        "done": true,
+        "completed": "2024-10-01"
+      }
+    ]
+  }
+]"#;
+        assert_eq!(json, expected);
+
+        let markdown = export(&collection, Format::Markdown).expect("markdown export");
+        assert_eq!(markdown, "## work\n\n- [ ] write \"report\"\n- [x] email\n");
+        assert!("csv".parse::<Format>().is_err());
+    }
+}
@@ -1,4 +1,10 @@
+pub mod date;
+pub mod deps;
+pub mod export;
 pub mod filesystem;
 pub mod parser;
+pub mod query;
+pub mod recur;
+pub mod sort;
 pub mod todo;
 pub use todo::TodoList;
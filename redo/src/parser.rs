@@ -1,5 +1,6 @@
 use std::ops::IndexMut;
 
+use crate::date::Date;
 use crate::todo::{Todo, TodoList, TodoListCollection, TodoStatus};
 
 fn is_valid(start: &str) -> bool {
@@ -19,7 +20,11 @@ impl From<String> for Todo {
             true => TodoStatus::Complete,
             false => TodoStatus::Incomplete,
         };
-        Todo { status, data }
+        Todo {
+            status,
+            data,
+            ..Default::default()
+        }
     }
 }
 
@@ -33,10 +38,56 @@ pub fn parse_todo(line: &str) -> Option<Todo> {
     } else {
         todo.status = TodoStatus::Incomplete;
     }
-    todo.data = line[3..].trim().to_string();
+    // the timestamps are the `created:`, `done:` and `modified:` dates at the end of the line
+    let mut data = line[3..].trim();
+    loop {
+        let (rest, word) = data.rsplit_once(char::is_whitespace).unwrap_or(("", data));
+        if !read_timestamp(&mut todo, word) {
+            break;
+        }
+        data = rest.trim_end();
+    }
+    todo.data = data.to_string();
+    // the modified date is only written when it is later than the others
+    todo.modified = todo.modified.or(todo.created.max(todo.completed));
     Some(todo)
 }
 
+fn read_timestamp(todo: &mut Todo, word: &str) -> bool {
+    let Some((key, value)) = word.split_once(':') else {
+        return false;
+    };
+    let Ok(date) = value.parse::<Date>() else {
+        return false;
+    };
+    let field = match key {
+        "created" => &mut todo.created,
+        "done" => &mut todo.completed,
+        "modified" => &mut todo.modified,
+        _ => return false,
+    };
+    *field = Some(date);
+    true
+}
+
+/// the timestamps of the todo as they are written after its text
+fn timestamps(todo: &Todo) -> String {
+    let mut words = String::new();
+    if let Some(created) = todo.created {
+        words.push_str(&format!(" created:{created}"));
+    }
+    if let Some(completed) = todo.completed {
+        words.push_str(&format!(" done:{completed}"));
+    }
+    match todo.modified {
+        Some(modified) if todo.modified > todo.created.max(todo.completed) => {
+            words.push_str(&format!(" modified:{modified}"))
+        }
+        _ => {}
+    }
+    words
+}
+
 pub fn parse(content: &str) -> Result<TodoList, String> {
     if content.is_empty() {
         return Err("Could not parse because contents was empty".to_string());
@@ -51,7 +102,6 @@ pub fn parse(content: &str) -> Result<TodoList, String> {
 
     let data = lines
         .map(|line| parse_todo(line).unwrap_or_default())
-        .map(Into::into)
         .collect::<Vec<_>>();
 
     let list = TodoList { title, data };
@@ -66,6 +116,16 @@ fn is_collection(line: &str) -> bool {
 }
 
 pub fn parse_collection(content: &str) -> Result<TodoListCollection, String> {
+    parse_lines(content, false)
+}
+
+/// like [`parse_collection`] but lines that are not a list, a todo or a note are an error instead of being
+/// skipped, for content that was just typed where such lines are mistakes
+pub fn parse_strict(content: &str) -> Result<TodoListCollection, String> {
+    parse_lines(content, true)
+}
+
+fn parse_lines(content: &str, strict: bool) -> Result<TodoListCollection, String> {
     if content.is_empty() {
         return Err("Could not parse because contents was empty".to_string());
     }
@@ -74,8 +134,37 @@ pub fn parse_collection(content: &str) -> Result<TodoListCollection, String> {
     let mut collection = TodoListCollection::default();
     let mut current_collection: usize = 0;
 
-    for line in lines {
-        let line = line.trim();
+    // the length and the line of the fence of the note the lines are inside of
+    let mut fence: Option<(usize, usize)> = None;
+    for (number, raw) in lines.into_iter().enumerate() {
+        let line = raw.trim();
+        // notes are the indented or fenced lines under a todo
+        let last = collection
+            .lists
+            .get_mut(current_collection.saturating_sub(1))
+            .and_then(|list| list.data.last_mut());
+        if let Some(todo) = last {
+            if let Some((open, _)) = fence {
+                // only a fence as long as the one it was opened with closes it, shorter ones are part of the note
+                match fence_len(line).is_some_and(|len| len >= open) {
+                    true => fence = None,
+                    false => push_note(todo, unindent(raw)),
+                }
+                continue;
+            }
+            let indented = raw.starts_with(char::is_whitespace);
+            // a fence opens a note only where the note would be indented, so a stray one between todos is skipped
+            let note_indent = raw.starts_with(INDENT) || raw.starts_with('\t');
+            if let Some(len) = fence_len(line).filter(|_| note_indent) {
+                fence = Some((len, number));
+                continue;
+            }
+            if indented && !line.is_empty() && !is_collection(line) && parse_todo(line).is_none() {
+                push_note(todo, unindent(raw));
+                continue;
+            }
+        }
+
         match is_collection(line) {
             true => {
                 let collection_name = line.trim_matches(':');
@@ -83,20 +172,107 @@ pub fn parse_collection(content: &str) -> Result<TodoListCollection, String> {
                 current_collection += 1;
             }
             false => {
-                let list = &mut collection.lists.index_mut(current_collection.saturating_sub(1));
-                if let Some(todo) = parse_todo(line) {
-                    list.push_todo(todo)
+                let Some(todo) = parse_todo(line) else {
+                    if strict && !line.is_empty() {
+                        return Err(format!("line {} is not a list, a todo or a note: {line}", number + 1));
+                    }
+                    continue;
+                };
+                if collection.lists.is_empty() {
+                    return Err(format!("Could not parse because a todo is outside of a list: {line}"));
                 }
+                let list = &mut collection.lists.index_mut(current_collection.saturating_sub(1));
+                list.push_todo(todo)
             }
         }
     }
 
+    if let Some((_, number)) = fence {
+        return Err(format!(
+            "Could not parse because the note fenced on line {} is never closed",
+            number + 1
+        ));
+    }
     Ok(collection)
 }
 
+/// the shortest fence notes that could be mistaken for todos are put between
+const FENCE: &str = "```";
+
+/// the length of the fence `line` is, a line of at least as many backticks as [`FENCE`]
+fn fence_len(line: &str) -> Option<usize> {
+    (line.len() >= FENCE.len() && line.chars().all(|ch| ch == '`')).then_some(line.len())
+}
+
+/// the indentation of a note line, a tab or up to 4 spaces
+const INDENT: &str = "    ";
+
+fn unindent(line: &str) -> &str {
+    let line = line.trim_end();
+    if let Some(rest) = line.strip_prefix('\t') {
+        return rest;
+    }
+    let spaces = line.len() - line.trim_start_matches(' ').len();
+    &line[usize::min(spaces, INDENT.len())..]
+}
+
+fn push_note(todo: &mut Todo, line: &str) {
+    if !todo.note.is_empty() {
+        todo.note.push('\n');
+    }
+    todo.note.push_str(line);
+}
+
+/// the note indented under its todo, fenced when a line of it would not read back as part of the note
+fn write_note(content: &mut String, note: &str) {
+    if note.is_empty() {
+        return;
+    }
+    let fenced = note.split('\n').any(|line| {
+        let line = line.trim();
+        line.is_empty() || fence_len(line).is_some() || is_collection(line) || parse_todo(line).is_some()
+    });
+    // longer than any run of backticks in the note so none of its lines closes the fence
+    let longest = note.split(|ch| ch != '`').map(str::len).max().unwrap_or(0);
+    let fence = "`".repeat(usize::max(FENCE.len(), longest + 1));
+    if fenced {
+        content.push_str(&format!("{INDENT}{fence}\n"));
+    }
+    for line in note.split('\n') {
+        match line.is_empty() {
+            true => content.push('\n'),
+            false => content.push_str(&format!("{INDENT}{line}\n")),
+        }
+    }
+    if fenced {
+        content.push_str(&format!("{INDENT}{fence}\n"));
+    }
+}
+
+/// the collection in the format [`parse_collection`] reads
+pub fn serialize(collection: &TodoListCollection) -> String {
+    let mut content = String::default();
+    for list in &collection.lists {
+        content.push_str(&format!("{}:\n", list.title));
+        content.push_str(&serialize_todos(&list.data));
+    }
+    content
+}
+
+/// the todos with their timestamps and notes, without the title of their list
+pub fn serialize_todos(todos: &[Todo]) -> String {
+    let mut content = String::default();
+    for todo in todos {
+        content.push_str(&format!("{} {}{}\n", todo.status, todo.data, timestamps(todo)));
+        write_note(&mut content, &todo.note);
+    }
+    content
+}
+
 #[cfg(test)]
 mod test {
-    use super::parse_collection;
+    use super::{parse_collection, parse_strict, serialize};
+    use crate::date::Date;
 
     #[test]
     fn parse_collection_test() {
@@ -107,4 +283,68 @@ mod test {
         assert!(collection.lists.len() == 1);
         //panic!();
     }
+
+    #[test]
+    fn serialize_test() {
+        let content = "[workouts]:\n[ ] push ups\n[x] squats\n[chores]:\n[ ] dishes\n";
+        let collection = parse_collection(content).expect("");
+        assert_eq!(serialize(&collection), content);
+    }
+
+    #[test]
+    fn notes_test() {
+        let content = "[work]:\n[ ] report\n    outline first\n        then draft\n[ ] email\n    ```\n    [ ] not a todo\n\n    ```\n[ ] call\n";
+        let collection = parse_collection(content).expect("");
+        let todos = &collection.lists[0].data;
+        assert_eq!(todos.len(), 3);
+        assert_eq!(todos[0].note, "outline first\n    then draft");
+        assert_eq!(todos[1].note, "[ ] not a todo\n");
+        assert!(todos[2].note.is_empty());
+        assert_eq!(serialize(&collection), content);
+
+        // a fence in the note gets a longer one around it instead of ending the note early
+        let mut collection = parse_collection("[work]:\n[ ] a\n[ ] b\n[home]:\n[ ] c\n").expect("");
+        collection.lists[0].data[0].note = "x\n```\ny".to_string();
+        let content = serialize(&collection);
+        assert!(content.contains("    ````\n"));
+        assert_eq!(parse_collection(&content), Ok(collection));
+
+        // a fence that is not indented is not a note of the todo above it
+        let stray = "[work]:\n[ ] a\n```\n[ ] b\n```\n[home]:\n[ ] c\n";
+        let collection = parse_collection(stray).expect("");
+        assert_eq!(collection.lists[0].data.len(), 2);
+        assert!(collection.lists[0].data[0].note.is_empty());
+        let content = serialize(&collection);
+        assert_eq!(content, "[work]:\n[ ] a\n[ ] b\n[home]:\n[ ] c\n");
+        assert_eq!(parse_collection(&content), Ok(collection));
+        assert!(parse_strict(stray).is_err());
+
+        let unclosed = "[work]:\n[ ] a\n    ```\n    x\n[home]:\n[ ] c\n";
+        assert_eq!(
+            parse_collection(unclosed),
+            Err("Could not parse because the note fenced on line 3 is never closed".to_string())
+        );
+    }
+
+    #[test]
+    fn parse_strict_test() {
+        let content = "[work]:\n[ ] report\n    a note\n\nforgot the box";
+        assert!(parse_collection(content).is_ok());
+        assert_eq!(
+            parse_strict(content),
+            Err("line 5 is not a list, a todo or a note: forgot the box".to_string())
+        );
+    }
+
+    #[test]
+    fn timestamps_test() {
+        let content = "[chores]:\n[x] dishes  at 5 created:2024-10-01 done:2024-10-02\n[ ] mop created:2024-10-01 modified:2024-10-03\n";
+        let collection = parse_collection(content).expect("");
+        let dishes = &collection.lists[0].data[0];
+        assert_eq!(dishes.data, "dishes  at 5");
+        assert_eq!(dishes.completed, Date::new(2024, 10, 2));
+        assert_eq!(dishes.modified, dishes.completed);
+        assert_eq!(collection.lists[0].data[1].modified, Date::new(2024, 10, 3));
+        assert_eq!(serialize(&collection), content);
+    }
 }
@@ -0,0 +1,394 @@
+use crate::date::Date;
+use crate::todo::{list_name, Todo, TodoList, TodoListCollection, TodoStatus};
+
+/// A filter like `status:open tag:work due<7d list:backend`, every term has to match.
+///
+/// Terms are `status:open|done`, `tag:name` (or `#name`), `list:name`, and `due`, `done`, `created` or `modified`
+/// compared with `:`, `<`, `<=`, `>` or `>=` against a date (`YYYY-MM-DD`, `today`, `tomorrow`, `yesterday`, `3d`,
+/// `-2w`) or `:none`/`:any`.
+/// Anything else is text the todo has to contain, ignoring case unless it has uppercase letters in it.
+/// A term starting with `-` has to not match and `"quoted words"` are a single term.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Query {
+    terms: Vec<Term>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Term {
+    negated: bool,
+    filter: Filter,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Filter {
+    Text(String),
+    Status(TodoStatus),
+    Tag(String),
+    List(String),
+    Date(DateField, Comparison, Day),
+}
+
+/// the dates of a todo a query can compare
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DateField {
+    Due,
+    Done,
+    Created,
+    Modified,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Comparison {
+    Less,
+    LessEqual,
+    Equal,
+    GreaterEqual,
+    Greater,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Day {
+    None,
+    Any,
+    Date(Date),
+    /// days from today, resolved when the query is evaluated so saved queries keep working
+    Relative(i64),
+}
+
+/// A todo matching a query together with the list it is in.
+#[derive(Debug, Clone, Copy)]
+pub struct Match<'a> {
+    pub list: &'a TodoList,
+    pub todo: &'a Todo,
+    pub list_index: usize,
+    pub todo_index: usize,
+}
+
+impl Query {
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    /// the text the todos have to contain, used to highlight matches
+    pub fn text(&self) -> impl Iterator<Item = &str> {
+        self.terms.iter().filter_map(|term| match (&term.filter, term.negated) {
+            (Filter::Text(text), false) => Some(text.as_str()),
+            _ => None,
+        })
+    }
+
+    pub fn matches(&self, list: &TodoList, todo: &Todo) -> bool {
+        self.matches_on(list, todo, Date::today())
+    }
+
+    /// like [`Query::matches`] with relative dates counted from `today`
+    pub fn matches_on(&self, list: &TodoList, todo: &Todo, today: Date) -> bool {
+        self.terms
+            .iter()
+            .all(|term| term.filter.matches(list, todo, today) != term.negated)
+    }
+
+    /// whether a list title matches, only queries made of plain text can match titles
+    pub fn matches_title(&self, title: &str) -> bool {
+        !self.is_empty()
+            && self.terms.iter().all(|term| match &term.filter {
+                Filter::Text(text) => contains(title, text) != term.negated,
+                _ => false,
+            })
+    }
+}
+
+impl Filter {
+    fn matches(&self, list: &TodoList, todo: &Todo, today: Date) -> bool {
+        match self {
+            Filter::Text(text) => contains(&todo.data, text),
+            Filter::Status(status) => todo.status == *status,
+            Filter::Tag(tag) => todo.has_tag(tag),
+            Filter::List(name) => list.name().to_lowercase().contains(&name.to_lowercase()),
+            Filter::Date(field, comparison, day) => {
+                let todo_date = field.of(todo);
+                let date = match day {
+                    Day::None => return todo_date.is_none(),
+                    Day::Any => return todo_date.is_some(),
+                    Day::Date(date) => *date,
+                    Day::Relative(days) => match today.checked_add_days(*days) {
+                        Some(date) => date,
+                        None => return false,
+                    },
+                };
+                let Some(todo_date) = todo_date else {
+                    return false;
+                };
+                match comparison {
+                    Comparison::Less => todo_date < date,
+                    Comparison::LessEqual => todo_date <= date,
+                    Comparison::Equal => todo_date == date,
+                    Comparison::GreaterEqual => todo_date >= date,
+                    Comparison::Greater => todo_date > date,
+                }
+            }
+        }
+    }
+}
+
+impl DateField {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "due" => Some(DateField::Due),
+            "done" => Some(DateField::Done),
+            "created" => Some(DateField::Created),
+            "modified" => Some(DateField::Modified),
+            _ => None,
+        }
+    }
+
+    fn of(&self, todo: &Todo) -> Option<Date> {
+        match self {
+            DateField::Due => todo.due(),
+            DateField::Done => todo.completed,
+            DateField::Created => todo.created,
+            DateField::Modified => todo.modified,
+        }
+    }
+}
+
+/// `text` contains `pattern`, ignoring case unless the pattern has uppercase letters in it
+fn contains(text: &str, pattern: &str) -> bool {
+    match pattern.chars().any(char::is_uppercase) {
+        true => text.contains(pattern),
+        false => text.to_lowercase().contains(pattern),
+    }
+}
+
+impl std::str::FromStr for Query {
+    type Err = String;
+
+    fn from_str(query: &str) -> Result<Self, Self::Err> {
+        let terms = split(query)
+            .map_err(|e| format!("{e} in query"))?
+            .into_iter()
+            .map(|word| parse_term(&word))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self { terms })
+    }
+}
+
+/// splits on whitespace, keeping `"quoted words"` together
+pub fn split(line: &str) -> Result<Vec<String>, String> {
+    let mut words = vec![];
+    let mut word = String::new();
+    let mut quoted = false;
+    for ch in line.chars() {
+        match ch {
+            '"' => quoted = !quoted,
+            ch if ch.is_whitespace() && !quoted => {
+                if !word.is_empty() {
+                    words.push(std::mem::take(&mut word));
+                }
+            }
+            ch => word.push(ch),
+        }
+    }
+    if quoted {
+        return Err("unclosed `\"`".to_string());
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    Ok(words)
+}
+
+fn parse_term(word: &str) -> Result<Term, String> {
+    let (negated, word) = match word.strip_prefix('-') {
+        Some(rest) if !rest.is_empty() => (true, rest),
+        _ => (false, word),
+    };
+    let filter = match word.strip_prefix('#') {
+        Some(tag) if !tag.is_empty() => Filter::Tag(tag.to_string()),
+        _ => parse_filter(word)?,
+    };
+    Ok(Term { negated, filter })
+}
+
+fn parse_filter(word: &str) -> Result<Filter, String> {
+    let text = || Filter::Text(word.to_string());
+    let Some(idx) = word.find([':', '<', '>', '=']) else {
+        return Ok(text());
+    };
+    let (field, rest) = word.split_at(idx);
+    let (comparison, value) = match rest {
+        _ if rest.starts_with("<=") => (Comparison::LessEqual, &rest[2..]),
+        _ if rest.starts_with(">=") => (Comparison::GreaterEqual, &rest[2..]),
+        _ if rest.starts_with('<') => (Comparison::Less, &rest[1..]),
+        _ if rest.starts_with('>') => (Comparison::Greater, &rest[1..]),
+        _ => (Comparison::Equal, &rest[1..]),
+    };
+    let equal_only = |filter: Filter| match comparison {
+        Comparison::Equal => Ok(filter),
+        _ => Err(format!("`{field}` can only be matched with `:` in `{word}`")),
+    };
+    let date_field = DateField::from_name(field);
+    if value.is_empty() && (matches!(field, "status" | "tag" | "list") || date_field.is_some()) {
+        return Err(format!("missing value for `{field}` in `{word}`"));
+    }
+
+    match field {
+        "status" => {
+            let status = match value.to_lowercase().as_str() {
+                "open" | "todo" | "incomplete" => TodoStatus::Incomplete,
+                "done" | "complete" | "completed" => TodoStatus::Complete,
+                _ => return Err(format!("unknown status `{value}`, expected open or done")),
+            };
+            equal_only(Filter::Status(status))
+        }
+        "tag" => equal_only(Filter::Tag(value.trim_start_matches('#').to_string())),
+        "list" => equal_only(Filter::List(list_name(value).to_string())),
+        _ => {
+            // things like urls are searched for as text
+            let Some(date_field) = date_field else {
+                return Ok(text());
+            };
+            let day = match value.to_lowercase().as_str() {
+                "none" => return equal_only(Filter::Date(date_field, comparison, Day::None)),
+                "any" => return equal_only(Filter::Date(date_field, comparison, Day::Any)),
+                value => match parse_relative(value)? {
+                    Some(days) => Day::Relative(days),
+                    None => Day::Date(value.parse()?),
+                },
+            };
+            Ok(Filter::Date(date_field, comparison, day))
+        }
+    }
+}
+
+/// `today`, `tomorrow`, `yesterday` or an amount of days or weeks like `3d`, `-2w` as days from today, `None`
+/// when the value is neither
+fn parse_relative(value: &str) -> Result<Option<i64>, String> {
+    match value {
+        "today" => return Ok(Some(0)),
+        "tomorrow" => return Ok(Some(1)),
+        "yesterday" => return Ok(Some(-1)),
+        _ => {}
+    }
+    let (amount, week) = match (value.strip_suffix('d'), value.strip_suffix('w')) {
+        (Some(amount), _) => (amount, false),
+        (_, Some(amount)) => (amount, true),
+        _ => return Ok(None),
+    };
+    let Ok(amount) = amount.strip_prefix('+').unwrap_or(amount).parse::<i64>() else {
+        return Ok(None);
+    };
+    let days = match week {
+        true => amount.checked_mul(7),
+        false => Some(amount),
+    };
+    match days.filter(|days| Date::today().checked_add_days(*days).is_some()) {
+        Some(days) => Ok(Some(days)),
+        None => Err(format!("`{value}` is too far from today")),
+    }
+}
+
+impl TodoListCollection {
+    /// every todo matching `query` in the order they are in the collection
+    pub fn query(&self, query: &Query) -> Vec<Match<'_>> {
+        let today = Date::today();
+        let mut matches = vec![];
+        for (list_index, list) in self.lists.iter().enumerate() {
+            for (todo_index, todo) in list.data.iter().enumerate() {
+                if query.matches_on(list, todo, today) {
+                    matches.push(Match {
+                        list,
+                        todo,
+                        list_index,
+                        todo_index,
+                    });
+                }
+            }
+        }
+        matches
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Query;
+    use crate::date::Date;
+    use crate::parser::parse_collection;
+
+    fn texts(content: &str, query: &str, today: Date) -> Vec<String> {
+        let collection = parse_collection(content).expect("valid collection");
+        let query = query.parse::<Query>().expect("valid query");
+        let mut texts = vec![];
+        for list in &collection.lists {
+            for todo in &list.data {
+                if query.matches_on(list, todo, today) {
+                    texts.push(todo.data.clone());
+                }
+            }
+        }
+        texts
+    }
+
+    #[test]
+    fn test_query() {
+        let content = "[backend]:
+        [ ] fix login #work due:2024-10-03
+        [x] deploy #work due:2024-10-01
+        [ ] write docs #work due:2024-10-20
+        [home]:
+        [ ] dishes #work due:2024-10-02";
+        let today = Date::new(2024, 10, 1).expect("valid date");
+
+        let found = texts(content, "status:open tag:work due<7d list:backend", today);
+        assert_eq!(found, vec!["fix login #work due:2024-10-03"]);
+        assert_eq!(texts(content, "-#work", today), Vec::<String>::new());
+        assert_eq!(texts(content, "due:today", today), vec!["deploy #work due:2024-10-01"]);
+        assert_eq!(texts(content, "\"write docs\" due>=2024-10-20", today).len(), 1);
+        assert_eq!(texts(content, "DISHES", today).len(), 0);
+        assert_eq!(texts(content, "dishes status:open", today).len(), 1);
+    }
+
+    #[test]
+    fn test_query_timestamps() {
+        let content = "[chores]:
+        [x] dishes created:2024-09-20 done:2024-09-30
+        [x] laundry done:2024-09-20
+        [ ] mop created:2024-09-29";
+        let today = Date::new(2024, 10, 1).expect("valid date");
+
+        assert_eq!(texts(content, "done>=-7d", today), vec!["dishes"]);
+        assert_eq!(texts(content, "created:none", today), vec!["laundry"]);
+        assert_eq!(texts(content, "modified>2024-09-25", today), vec!["dishes", "mop"]);
+    }
+
+    #[test]
+    fn test_query_errors() {
+        assert!("status:maybe".parse::<Query>().is_err());
+        assert!("due<soon".parse::<Query>().is_err());
+        assert!("tag>work".parse::<Query>().is_err());
+        assert!("list:".parse::<Query>().is_err());
+        assert!("\"unclosed".parse::<Query>().is_err());
+        assert!("http://example.com".parse::<Query>().is_ok());
+        assert!("due:ü".parse::<Query>().is_err());
+        assert!("due:+999999999999999999w".parse::<Query>().is_err());
+        assert!("due<99999999999999d".parse::<Query>().is_err());
+        assert!("due>-2w".parse::<Query>().is_ok());
+    }
+
+    #[test]
+    fn test_collection_query() {
+        let content = "[a]:
+        [ ] one
+        [b]:
+        [ ] two
+        [x] one more";
+        let collection = parse_collection(content).expect("valid collection");
+        let matches = collection.query(&"one".parse().expect("valid query"));
+        let found = matches
+            .iter()
+            .map(|found| (found.list_index, found.todo_index))
+            .collect::<Vec<_>>();
+        assert_eq!(found, vec![(0, 0), (1, 1)]);
+        assert_eq!(matches[1].list.title, "[b]");
+    }
+}
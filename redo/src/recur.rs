@@ -0,0 +1,199 @@
+use crate::date::{days_in_month, Date};
+use crate::todo::{Todo, TodoList, TodoStatus};
+
+/// When a todo comes back after it is completed, from `rec:` in the todo.
+///
+/// `rec:daily`, `rec:weekly` or a weekday like `rec:mon`, `rec:monthly` or a day of the month like `rec:15th`
+/// repeat on a schedule, `rec:3d` and `rec:2w` come back that long after the todo is completed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Recurrence {
+    Daily,
+    /// on a day of the week, 0 is monday, `None` is the weekday of the due date
+    Weekly(Option<u8>),
+    /// on a day of the month, clamped to the length of the month, `None` is the day of the due date
+    Monthly(Option<u8>),
+    /// an amount of days after the todo is completed
+    After(i64),
+}
+
+const WEEKDAYS: [&str; 7] = [
+    "monday",
+    "tuesday",
+    "wednesday",
+    "thursday",
+    "friday",
+    "saturday",
+    "sunday",
+];
+
+impl std::str::FromStr for Recurrence {
+    type Err = String;
+
+    fn from_str(rule: &str) -> Result<Self, Self::Err> {
+        let rule = rule.to_lowercase();
+        let invalid = || {
+            format!(
+                "unknown recurrence `{rule}`, expected daily, weekly, a weekday, monthly, a day like 15th, 3d or 2w"
+            )
+        };
+        match rule.as_str() {
+            "daily" => return Ok(Recurrence::Daily),
+            "weekly" => return Ok(Recurrence::Weekly(None)),
+            "monthly" => return Ok(Recurrence::Monthly(None)),
+            _ => {}
+        }
+        if let Some(day) = WEEKDAYS
+            .iter()
+            .position(|name| rule.len() >= 3 && name.starts_with(rule.as_str()))
+        {
+            return Ok(Recurrence::Weekly(Some(day as u8)));
+        }
+
+        let split = rule.find(|ch: char| !ch.is_ascii_digit()).ok_or_else(invalid)?;
+        let (amount, unit) = rule.split_at(split);
+        let amount = amount.parse::<u8>().map_err(|_| invalid())?;
+        match unit {
+            "st" | "nd" | "rd" | "th" if (1..=31).contains(&amount) => Ok(Recurrence::Monthly(Some(amount))),
+            "d" if amount > 0 => Ok(Recurrence::After(i64::from(amount))),
+            "w" if amount > 0 => Ok(Recurrence::After(i64::from(amount) * 7)),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+impl Recurrence {
+    /// the due date of the next occurrence of a todo due on `due` that was completed on `completed`. Schedules skip
+    /// the days that have already passed, so a daily todo that was missed for a week comes back once.
+    pub fn next(&self, due: Option<Date>, completed: Date) -> Date {
+        let base = due.unwrap_or(completed);
+        let after = base.max(completed.add_days(-1));
+        match self {
+            Recurrence::Daily => after.add_days(1),
+            Recurrence::Weekly(day) => {
+                let day = day.unwrap_or(base.weekday());
+                let ahead = (day + 7 - after.weekday()) % 7;
+                match ahead {
+                    0 => after.add_days(7),
+                    ahead => after.add_days(i64::from(ahead)),
+                }
+            }
+            Recurrence::Monthly(day) => {
+                let day = day.unwrap_or(base.day);
+                let (mut year, mut month) = (after.year, after.month);
+                loop {
+                    let date = Date::new(year, month, u8::min(day, days_in_month(year, month)));
+                    if let Some(date) = date.filter(|date| *date > after) {
+                        return date;
+                    }
+                    (year, month) = match month {
+                        12 => (year + 1, 1),
+                        month => (year, month + 1),
+                    };
+                }
+            }
+            Recurrence::After(days) => completed.add_days(*days),
+        }
+    }
+}
+
+impl Todo {
+    /// the rule from `rec:`
+    pub fn recurrence(&self) -> Option<Recurrence> {
+        self.meta("rec")?.parse().ok()
+    }
+
+    /// the open todo that follows this one when it is completed on `completed`, `None` when it does not recur. It
+    /// keeps the notes, they usually say how the todo is done every time
+    pub fn next_occurrence(&self, completed: Date) -> Option<Todo> {
+        let due = self.recurrence()?.next(self.due(), completed);
+        let mut next = Todo::new_on(self.data.clone(), completed);
+        next.set_meta("due", &due.to_string());
+        next.note = self.note.clone();
+        Some(next)
+    }
+}
+
+impl TodoList {
+    /// completes or reopens the todo at `index`, completing a recurring todo adds its next occurrence after it and
+    /// returns where it was added
+    pub fn set_status(&mut self, index: usize, status: TodoStatus) -> Option<usize> {
+        self.set_status_on(index, status, Date::today())
+    }
+
+    pub fn set_status_on(&mut self, index: usize, status: TodoStatus, today: Date) -> Option<usize> {
+        let todo = self.data.get_mut(index)?;
+        let completing = todo.status == TodoStatus::Incomplete && status == TodoStatus::Complete;
+        todo.set_status_on(status, today);
+        if !completing {
+            return None;
+        }
+        let next = todo.next_occurrence(today)?;
+        // only the next occurrence recurs, so completing this one again does not add another
+        todo.remove_meta("rec");
+        self.data.insert(index + 1, next);
+        Some(index + 1)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Recurrence;
+    use crate::date::Date;
+    use crate::parser::parse_collection;
+    use crate::todo::TodoStatus;
+
+    fn date(value: &str) -> Date {
+        value.parse().expect("valid date")
+    }
+
+    #[test]
+    fn test_next() {
+        // 2024-10-07 is a monday
+        let monday = date("2024-10-07");
+        let next = |rule: &str, due: Option<Date>, completed: Date| {
+            rule.parse::<Recurrence>()
+                .expect("valid rule")
+                .next(due, completed)
+                .to_string()
+        };
+        assert_eq!(next("daily", Some(monday), monday), "2024-10-08");
+        assert_eq!(next("daily", Some(monday), monday.add_days(5)), "2024-10-12");
+        assert_eq!(next("weekly", Some(monday), monday), "2024-10-14");
+        assert_eq!(next("fri", None, monday), "2024-10-11");
+        assert_eq!(
+            next("monthly", Some(date("2024-01-31")), date("2024-01-30")),
+            "2024-02-29"
+        );
+        assert_eq!(next("15th", None, date("2024-10-15")), "2024-11-15");
+        assert_eq!(next("3d", Some(monday), monday.add_days(2)), "2024-10-12");
+        assert!("fortnightly".parse::<Recurrence>().is_err());
+        assert!("32nd".parse::<Recurrence>().is_err());
+    }
+
+    #[test]
+    fn test_complete_recurring() {
+        let content = "[work]:\n[ ] standup rec:daily due:2024-10-07\n[ ] lunch";
+        let mut list = parse_collection(content).expect("valid list").lists.remove(0);
+        let today = date("2024-10-07");
+
+        assert_eq!(list.set_status_on(0, TodoStatus::Complete, today), Some(1));
+        assert_eq!(list.data[0].to_string(), "[x] standup due:2024-10-07");
+        assert_eq!(list.data[1].to_string(), "[ ] standup rec:daily due:2024-10-08");
+        assert_eq!(list.data[1].created, Some(today));
+        assert_eq!(list.set_status_on(0, TodoStatus::Incomplete, today), None);
+        assert_eq!(list.set_status_on(2, TodoStatus::Complete, today), None);
+        assert_eq!(list.len(), 3);
+    }
+
+    #[test]
+    fn test_complete_recurring_with_note() {
+        let content = "[work]:\n[ ] water  the plants rec:3d  due:2024-10-07\n    ```\n    the cactus  too\n    ```\n";
+        let mut list = parse_collection(content).expect("valid list").lists.remove(0);
+        let today = date("2024-10-07");
+
+        assert_eq!(list.set_status_on(0, TodoStatus::Complete, today), Some(1));
+        assert_eq!(list.data[0].data, "water  the plants  due:2024-10-07");
+        assert_eq!(list.data[1].data, "water  the plants rec:3d  due:2024-10-10");
+        assert_eq!(list.data[1].note, "the cactus  too");
+    }
+}
@@ -0,0 +1,126 @@
+use std::cmp::Ordering;
+
+use crate::todo::{Todo, TodoList};
+
+/// What todos can be sorted by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortKey {
+    /// open todos before completed ones
+    Status,
+    /// highest priority first, todos without one last
+    Priority,
+    /// earliest due date first, todos without one last
+    Due,
+    /// alphabetically, ignoring case
+    Text,
+}
+
+impl SortKey {
+    pub const NAMES: [&'static str; 4] = ["status", "priority", "due", "text"];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SortKey::Status => "status",
+            SortKey::Priority => "priority",
+            SortKey::Due => "due",
+            SortKey::Text => "text",
+        }
+    }
+
+    pub fn compare(&self, a: &Todo, b: &Todo) -> Ordering {
+        match self {
+            SortKey::Status => a.status.cmp(&b.status),
+            SortKey::Priority => missing_last(a.priority(), b.priority()),
+            SortKey::Due => missing_last(a.due(), b.due()),
+            SortKey::Text => a.data.to_lowercase().cmp(&b.data.to_lowercase()),
+        }
+    }
+}
+
+fn missing_last<T: Ord>(a: Option<T>, b: Option<T>) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) => a.cmp(&b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+impl std::str::FromStr for SortKey {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "status" => Ok(SortKey::Status),
+            "priority" | "pri" => Ok(SortKey::Priority),
+            "due" => Ok(SortKey::Due),
+            "text" => Ok(SortKey::Text),
+            _ => Err(format!(
+                "unknown sort key `{name}`, expected one of {}",
+                SortKey::NAMES.join(", ")
+            )),
+        }
+    }
+}
+
+/// compares by the first key, using the next keys for todos that are equal
+pub fn compare(keys: &[SortKey], a: &Todo, b: &Todo) -> Ordering {
+    keys.iter()
+        .map(|key| key.compare(a, b))
+        .find(|ordering| ordering.is_ne())
+        .unwrap_or(Ordering::Equal)
+}
+
+impl TodoList {
+    /// reorders the todos by `keys`, todos that are equal in every key keep their order
+    pub fn sort_by_keys(&mut self, keys: &[SortKey]) {
+        self.data.sort_by(|a, b| compare(keys, a, b));
+    }
+
+    /// the indices of the todos in the order `keys` sorts them in, without changing the list
+    pub fn sorted(&self, keys: &[SortKey]) -> Vec<usize> {
+        let mut indices = (0..self.len()).collect::<Vec<_>>();
+        indices.sort_by(|a, b| compare(keys, &self.data[*a], &self.data[*b]));
+        indices
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::SortKey;
+    use crate::parser::parse_collection;
+
+    #[test]
+    fn test_sort() {
+        let content = "[list]:\n[x] b due:2024-01-01\n[ ] c\n[ ] a due:2024-02-01\n[ ] B due:2024-01-01";
+        let mut list = parse_collection(content).expect("valid list").lists.remove(0);
+
+        list.sort_by_keys(&[SortKey::Status, SortKey::Due]);
+        let texts = list.data.iter().map(|todo| todo.data.as_str()).collect::<Vec<_>>();
+        assert_eq!(
+            texts,
+            vec!["B due:2024-01-01", "a due:2024-02-01", "c", "b due:2024-01-01"]
+        );
+
+        assert_eq!(list.sorted(&[SortKey::Text]), vec![1, 0, 3, 2]);
+        list.sort_by_keys(&[SortKey::Text]);
+        let texts = list.data.iter().map(|todo| todo.data.as_str()).collect::<Vec<_>>();
+        assert_eq!(
+            texts,
+            vec!["a due:2024-02-01", "B due:2024-01-01", "b due:2024-01-01", "c"]
+        );
+    }
+    #[test]
+    fn test_sort_priority() {
+        let content = "[list]:\n[ ] none\n[ ] later pri:low\n[x] done pri:high\n[ ] now pri:high\n[ ] soon pri:m";
+        let mut list = parse_collection(content).expect("valid list").lists.remove(0);
+
+        list.sort_by_keys(&[SortKey::Status, SortKey::Priority]);
+        let texts = list.data.iter().map(|todo| todo.data.as_str()).collect::<Vec<_>>();
+        assert_eq!(
+            texts,
+            vec!["now pri:high", "soon pri:m", "later pri:low", "none", "done pri:high"]
+        );
+        assert!("urgency".parse::<SortKey>().is_err());
+    }
+}
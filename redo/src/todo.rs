@@ -1,5 +1,6 @@
-use std::ops::{Index, IndexMut};
+use std::ops::{Index, IndexMut, Range};
 
+use crate::date::Date;
 use crate::parser;
 
 #[derive(Debug, Default, PartialEq, Eq, PartialOrd, Ord, Clone)]
@@ -37,10 +38,39 @@ impl std::fmt::Display for TodoStatus {
     }
 }
 
-#[derive(Debug, Default, Clone)]
+/// How important a todo is, from `pri:high`, `pri:medium` or `pri:low`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Priority {
+    High,
+    Medium,
+    Low,
+}
+
+impl std::str::FromStr for Priority {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_lowercase().as_str() {
+            "high" | "h" => Ok(Priority::High),
+            "medium" | "med" | "m" => Ok(Priority::Medium),
+            "low" | "l" => Ok(Priority::Low),
+            _ => Err(format!("unknown priority `{value}`, expected high, medium or low")),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
 pub struct Todo {
     pub data: String,
     pub status: TodoStatus,
+    /// the day the todo was added on
+    pub created: Option<Date>,
+    /// the day the todo was completed on, `None` while it is open
+    pub completed: Option<Date>,
+    /// the day the text or the status last changed
+    pub modified: Option<Date>,
+    /// free text under the todo, it can span several lines
+    pub note: String,
 }
 
 impl std::fmt::Display for Todo {
@@ -50,6 +80,52 @@ impl std::fmt::Display for Todo {
 }
 
 impl Todo {
+    /// an open todo created today
+    pub fn new(data: impl Into<String>) -> Self {
+        Self::new_on(data, Date::today())
+    }
+
+    pub fn new_on(data: impl Into<String>, today: Date) -> Self {
+        Self {
+            data: data.into(),
+            created: Some(today),
+            modified: Some(today),
+            ..Default::default()
+        }
+    }
+
+    pub fn toggle(&mut self) {
+        self.toggle_on(Date::today());
+    }
+
+    pub fn toggle_on(&mut self, today: Date) {
+        let mut status = self.status.clone();
+        status.toggle();
+        self.set_status_on(status, today);
+    }
+
+    /// completes or reopens the todo, recording the day it was completed on
+    pub fn set_status(&mut self, status: TodoStatus) {
+        self.set_status_on(status, Date::today());
+    }
+
+    pub fn set_status_on(&mut self, status: TodoStatus, today: Date) {
+        if self.status == status {
+            return;
+        }
+        self.completed = match status {
+            TodoStatus::Complete => Some(today),
+            TodoStatus::Incomplete => None,
+        };
+        self.status = status;
+        self.modified = Some(today);
+    }
+
+    /// records that the text was changed today
+    pub fn touch(&mut self) {
+        self.modified = Some(Date::today());
+    }
+
     pub fn is_empty(&self) -> bool {
         self.len() == 0
     }
@@ -57,25 +133,168 @@ impl Todo {
     pub fn len(&self) -> usize {
         self.data.len()
     }
+
+    /// the words of the todo starting with `#`, without the `#`
+    pub fn tags(&self) -> impl Iterator<Item = &str> {
+        self.data
+            .split_whitespace()
+            .filter_map(|word| word.strip_prefix('#'))
+            .filter(|tag| !tag.is_empty())
+    }
+
+    pub fn has_tag(&self, tag: &str) -> bool {
+        let tag = tag.trim_start_matches('#');
+        self.tags().any(|other| other.eq_ignore_ascii_case(tag))
+    }
+
+    /// the value of the first `key:value` word of the todo
+    pub fn meta(&self, key: &str) -> Option<&str> {
+        self.data.split_whitespace().find_map(|word| {
+            let (name, value) = word.split_once(':')?;
+            (name == key && !value.is_empty()).then_some(value)
+        })
+    }
+
+    /// the date from `due:YYYY-MM-DD`
+    pub fn due(&self) -> Option<Date> {
+        self.meta("due")?.parse().ok()
+    }
+
+    /// the priority from `pri:high`, `pri:medium` or `pri:low`
+    pub fn priority(&self) -> Option<Priority> {
+        self.meta("pri")?.parse().ok()
+    }
+
+    /// replaces the value of the first `key:value` word, appending one when there is none
+    pub fn set_meta(&mut self, key: &str, value: &str) {
+        let word = format!("{key}:{value}");
+        match self.meta_words(key).first() {
+            Some(range) => self.data.replace_range(range.clone(), &word),
+            None if self.data.is_empty() => self.data = word,
+            None => self.data = format!("{} {word}", self.data),
+        }
+    }
+
+    /// removes every `key:value` word together with the whitespace in front of it, the rest of the text is left as
+    /// it was
+    pub fn remove_meta(&mut self, key: &str) {
+        // from the back, so the ranges of the words before stay where they are
+        for range in self.meta_words(key).into_iter().rev() {
+            let start = self.data[..range.start].trim_end().len();
+            let end = match start {
+                // the first word takes the whitespace after it instead
+                0 => self.data.len() - self.data[range.end..].trim_start().len(),
+                _ => range.end,
+            };
+            self.data.replace_range(start..end, "");
+        }
+    }
+
+    /// the byte ranges of the `key:value` words
+    fn meta_words(&self, key: &str) -> Vec<Range<usize>> {
+        let mut words = vec![];
+        let mut start = None;
+        for (idx, ch) in self.data.char_indices().chain([(self.data.len(), ' ')]) {
+            match (ch.is_whitespace(), start) {
+                (true, Some(word_start)) => {
+                    words.push(word_start..idx);
+                    start = None;
+                }
+                (false, None) => start = Some(idx),
+                _ => {}
+            }
+        }
+        words.retain(|range: &Range<usize>| {
+            self.data[range.clone()]
+                .split_once(':')
+                .is_some_and(|(name, value)| name == key && !value.is_empty())
+        });
+        words
+    }
+
+    /// appends `#tag` to the todo unless it already has it
+    pub fn add_tag(&mut self, tag: &str) {
+        let tag = tag.trim().trim_start_matches('#');
+        if tag.is_empty() || tag.contains(char::is_whitespace) || self.has_tag(tag) {
+            return;
+        }
+        if !self.data.is_empty() {
+            self.data.push(' ');
+        }
+        self.data.push('#');
+        self.data.push_str(tag);
+    }
 }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
 pub struct TodoList {
     pub title: String,
     pub data: Vec<Todo>,
 }
 
+/// How far along a list is.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    pub done: usize,
+    pub total: usize,
+}
+
+impl Stats {
+    pub fn open(&self) -> usize {
+        self.total - self.done
+    }
+
+    /// whether the list has todos and every one of them is done
+    pub fn is_complete(&self) -> bool {
+        self.total > 0 && self.done == self.total
+    }
+
+    /// the part of the todos that is done, rounded down, 0 for a list without todos
+    pub fn percent(&self) -> usize {
+        match self.total {
+            0 => 0,
+            total => self.done * 100 / total,
+        }
+    }
+}
+
 impl TodoList {
     pub fn is_empty(&self) -> bool {
         self.data.is_empty()
     }
 
+    pub fn stats(&self) -> Stats {
+        Stats {
+            done: self
+                .data
+                .iter()
+                .filter(|todo| todo.status == TodoStatus::Complete)
+                .count(),
+            total: self.len(),
+        }
+    }
+
     pub fn len(&self) -> usize {
         self.data.len()
     }
 
+    /// the title without the surrounding `[]`
+    pub fn name(&self) -> &str {
+        list_name(&self.title)
+    }
+
+    pub fn is_archive(&self) -> bool {
+        self.archived_from().is_some()
+    }
+
+    /// the name of the list an archive section holds the todos of, `None` for lists that are not archives
+    pub fn archived_from(&self) -> Option<&str> {
+        self.name().strip_prefix(ARCHIVE).filter(|name| !name.is_empty())
+    }
+
+    /// the amount of characters of the todo at `index`, the columns the cursor can be on
     pub fn len_line(&self, index: usize) -> usize {
-        self.data[index].len()
+        self.data[index].data.chars().count()
     }
 
     pub fn new(title: String, contents: &str) -> Self {
@@ -86,11 +305,7 @@ impl TodoList {
     }
 
     pub fn push_str(&mut self, contents: &str) {
-        let todo = Todo {
-            data: contents.to_string(),
-            status: TodoStatus::Incomplete,
-        };
-        self.data.push(todo);
+        self.data.push(Todo::new(contents));
     }
 
     pub fn push_todo(&mut self, todo: Todo) {
@@ -112,7 +327,15 @@ impl std::fmt::Display for TodoList {
     }
 }
 
-#[derive(Debug, Default, Clone)]
+/// what the titles of archive sections start with, `[archive/work]` holds the archived todos of `[work]`
+pub const ARCHIVE: &str = "archive/";
+
+/// the title of the section the todos of the list called `name` are archived into
+pub fn archive_title(name: &str) -> String {
+    format!("[{ARCHIVE}{name}]")
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
 pub struct TodoListCollection {
     pub lists: Vec<TodoList>,
 }
@@ -130,6 +353,82 @@ impl TodoListCollection {
         Some(list)
     }
 
+    /// finds a list by its title, ignoring case and the surrounding `[]`
+    pub fn find(&self, name: &str) -> Option<usize> {
+        let name = list_name(name.trim());
+        self.lists
+            .iter()
+            .position(|list| list.name().eq_ignore_ascii_case(name))
+    }
+
+    /// moves the completed todos of every list to the end of its archive section, see [`TodoListCollection::archive_on`]
+    pub fn archive(&mut self, older_than: Option<u32>) -> usize {
+        self.archive_on(Date::today(), older_than)
+    }
+
+    /// moves the completed todos of every list to the end of its `[archive/<list>]` section, creating it when
+    /// needed. With `older_than` only todos completed at least that many days before `today` are moved, todos
+    /// completed on an unknown day count as old enough and are recorded as completed `today`. Returns how many
+    /// todos were moved.
+    pub fn archive_on(&mut self, today: Date, older_than: Option<u32>) -> usize {
+        let cutoff = older_than.map(|days| today.add_days(-i64::from(days)));
+        let mut moved = 0;
+        for idx in 0..self.lists.len() {
+            if self.lists[idx].is_archive() {
+                continue;
+            }
+            let (mut done, open) = self.lists[idx].data.drain(..).partition(|todo: &Todo| {
+                todo.status == TodoStatus::Complete
+                    && cutoff.is_none_or(|cutoff| todo.completed.is_none_or(|completed| completed <= cutoff))
+            });
+            self.lists[idx].data = open;
+            if done.is_empty() {
+                continue;
+            }
+
+            for todo in &mut done {
+                todo.completed.get_or_insert(today);
+            }
+            moved += done.len();
+            let title = archive_title(self.lists[idx].name());
+            match self.find(&title) {
+                Some(archive) => self.lists[archive].data.extend(done),
+                None => self.push(TodoList { title, data: done }),
+            }
+        }
+        moved
+    }
+
+    /// moves the `todos` of an archive section back into their list as open todos, creating the list when needed
+    /// and removing the section once it is empty. Returns how many todos were restored.
+    pub fn restore(&mut self, archive: usize, todos: &[usize]) -> usize {
+        let Some(name) = self.lists.get(archive).and_then(TodoList::archived_from) else {
+            return 0;
+        };
+        let name = name.to_string();
+        let mut restored = vec![];
+        for idx in todos.iter().rev() {
+            if *idx < self.lists[archive].len() {
+                let mut todo = self.lists[archive].data.remove(*idx);
+                todo.set_status(TodoStatus::Incomplete);
+                restored.insert(0, todo);
+            }
+        }
+
+        let count = restored.len();
+        match self.find(&name) {
+            Some(list) => self.lists[list].data.extend(restored),
+            None => self.push(TodoList {
+                title: format!("[{name}]"),
+                data: restored,
+            }),
+        }
+        if self.lists[archive].is_empty() {
+            self.lists.remove(archive);
+        }
+        count
+    }
+
     pub fn get_mut_todo_list(&mut self, index: usize) -> Option<&mut TodoList> {
         if self.lists.is_empty() || index > self.lists.len() {
             return None;
@@ -145,8 +444,15 @@ impl std::fmt::Display for TodoListCollection {
     }
 }
 
+/// a list name typed with or without the `[]` of its title, without them
+pub fn list_name(title: &str) -> &str {
+    title.trim_start_matches('[').trim_end_matches(']')
+}
+
 #[cfg(test)]
 mod test {
+    use super::{Stats, Todo, TodoList, TodoListCollection, TodoStatus};
+    use crate::date::Date;
     use crate::parser;
 
     #[test]
@@ -160,4 +466,114 @@ mod test {
         let res = parser::parse(data).expect("");
         assert!(!res.data.is_empty());
     }
+
+    #[test]
+    fn test_tags() {
+        let mut todo = Todo {
+            data: "write report #work".to_string(),
+            ..Default::default()
+        };
+        todo.add_tag("#Work");
+        todo.add_tag("urgent");
+        assert_eq!(todo.data, "write report #work #urgent");
+        assert!(todo.has_tag("URGENT"));
+        assert_eq!(todo.tags().collect::<Vec<_>>(), vec!["work", "urgent"]);
+    }
+
+    #[test]
+    fn test_due() {
+        let todo = Todo {
+            data: "pay rent due:2024-11-01 http://example.com".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(todo.meta("http"), Some("//example.com"));
+        assert_eq!(todo.due().map(|due| due.to_string()).as_deref(), Some("2024-11-01"));
+        assert_eq!(Todo::default().due(), None);
+    }
+
+    #[test]
+    fn test_archive() {
+        let content = "[work]:\n[x] report done:2024-09-01\n[ ] email\n[x] review\n[home]:\n[x] dishes done:2024-10-01";
+        let mut collection = parser::parse_collection(content).expect("");
+        let today = Date::new(2024, 10, 5).expect("valid date");
+        assert_eq!(collection.archive_on(today, Some(7)), 2);
+        assert_eq!(collection.lists[0].len(), 1);
+        assert_eq!(collection.lists[1].len(), 1);
+        assert_eq!(collection.lists[2].title, "[archive/work]");
+        assert_eq!(collection.lists[2].data[1].completed, Some(today));
+        assert_eq!(collection.lists[2].data[0].completed, Date::new(2024, 9, 1));
+        assert_eq!(collection.archive_on(today, None), 1);
+        assert_eq!(collection.lists[3].title, "[archive/home]");
+        assert!(!collection.lists[3].is_empty());
+        assert_eq!(collection.archive_on(today, None), 0);
+
+        assert_eq!(collection.restore(2, &[1]), 1);
+        assert_eq!(collection.lists[0].data[1].to_string(), "[ ] review");
+        assert_eq!(collection.restore(2, &[0]), 1);
+        assert_eq!(collection.find("archive/work"), None);
+        assert_eq!(collection.restore(0, &[0]), 0);
+    }
+
+    #[test]
+    fn test_timestamps() {
+        let monday = Date::new(2024, 10, 7).expect("valid date");
+        let tuesday = monday.add_days(1);
+        let mut todo = Todo::new_on("water plants", monday);
+        assert_eq!(todo.created, Some(monday));
+
+        todo.toggle_on(tuesday);
+        assert_eq!(todo.status, TodoStatus::Complete);
+        assert_eq!((todo.completed, todo.modified), (Some(tuesday), Some(tuesday)));
+        todo.set_status_on(TodoStatus::Complete, tuesday.add_days(1));
+        assert_eq!(todo.completed, Some(tuesday));
+        todo.toggle_on(tuesday);
+        assert_eq!(todo.completed, None);
+    }
+
+    #[test]
+    fn test_meta() {
+        let mut todo = Todo {
+            data: "call mom due:2024-10-01".to_string(),
+            ..Default::default()
+        };
+        todo.set_meta("due", "2024-10-02");
+        todo.set_meta("done", "2024-10-03");
+        assert_eq!(todo.data, "call mom due:2024-10-02 done:2024-10-03");
+        assert_eq!(todo.meta("done"), Some("2024-10-03"));
+        todo.remove_meta("due");
+        assert_eq!(todo.data, "call mom done:2024-10-03");
+
+        // only the words themselves change, the spacing around the rest is kept
+        todo.data = "due:2024-10-01  call  mom due:2024-10-02\tlater".to_string();
+        todo.set_meta("due", "2024-11-01");
+        assert_eq!(todo.data, "due:2024-11-01  call  mom due:2024-10-02\tlater");
+        todo.remove_meta("due");
+        assert_eq!(todo.data, "call  mom\tlater");
+    }
+
+    #[test]
+    fn test_find_list() {
+        let mut collection = TodoListCollection::default();
+        collection.push(TodoList::new("[workouts]".to_string(), ""));
+        collection.push(TodoList::new("[Backend]".to_string(), ""));
+        assert_eq!(collection.find("backend"), Some(1));
+        assert_eq!(collection.find("[workouts]"), Some(0));
+        assert_eq!(collection.find("groceries"), None);
+    }
+
+    #[test]
+    fn test_stats() {
+        let content = "[workouts]:\n[x] run\n[ ] swim\n[x] lift";
+        let mut list = parser::parse_collection(content).expect("valid list").lists.remove(0);
+        let stats = list.stats();
+        assert_eq!(stats, Stats { done: 2, total: 3 });
+        assert_eq!((stats.open(), stats.percent()), (1, 66));
+        assert!(!stats.is_complete());
+
+        list.data[1].toggle();
+        assert!(list.stats().is_complete());
+        assert_eq!(list.stats().percent(), 100);
+        assert!(!TodoList::default().stats().is_complete());
+        assert_eq!(TodoList::default().stats().percent(), 0);
+    }
 }
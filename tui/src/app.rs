@@ -1,70 +1,139 @@
-use crossterm::event::read;
-use redo::{filesystem, parser};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
 
+use crossterm::event::{poll, read};
+use signal_hook::consts::{SIGHUP, SIGTERM};
+
+use crate::args::Args;
+use crate::config::Config;
 use crate::event::EventHandler;
-use crate::tui::{Interface, InterfaceState};
+use crate::tabs::Tab;
+use crate::tui::{self, Interface, InterfaceState};
 
 #[derive(Debug, Default)]
 pub struct App {
-    pub file: String,
     interface: Interface,
 }
 
-impl App {
-    pub fn init(args: std::env::Args) -> Self {
-        ratatui::init();
-
-        if args.len() <= 1 {
-            return App::default();
-        }
+/// why the event loop stopped
+enum Stop {
+    Quit(Result<(), String>),
+    Signal(i32),
+    Failed(String),
+}
 
-        let mut args = args.skip(1);
-        let file = match args.next() {
-            Some(data) => data,
-            None => {
-                return App::default();
-            }
-        };
+impl App {
+    /// opens every file, a file that can not be parsed is an error since saving it would throw its todos away
+    pub fn init(args: Args, config: Config) -> Result<Self, String> {
+        // every file is opened in its own tab, the same way `:open` opens them
+        let tabs = args
+            .files
+            .into_iter()
+            .map(|file| {
+                let mut tab = Tab::open(file)?;
+                tab.read_only |= args.read_only;
+                Ok(tab)
+            })
+            .collect::<Result<Vec<_>, String>>()?;
 
-        let content = filesystem::read(&file).unwrap_or_default();
-        let collection = parser::parse_collection(&content).unwrap_or_default();
+        // the terminal is given back before the panic is printed, the edits are saved once it is caught in `run`
+        let default_hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            tui::restore_terminal();
+            tracing::error!("{info}");
+            default_hook(info);
+        }));
 
-        let interface = Interface::new(collection);
+        let mut interface = Interface::new(tabs, config);
+        interface.all_read_only = args.read_only;
+        if let Some(list) = &args.list {
+            interface.open_list(list);
+        }
+        interface.find_recovery();
 
-        Self { file, interface }
+        Ok(Self { interface })
     }
 
-    pub fn run(&mut self) {
-        loop {
-            self.interface.draw();
-            self.interface.flush();
-            let event = read().unwrap();
+    /// runs until the app is quit, returning the exit code. Edits that can not be saved normally because of a panic,
+    /// a signal or a broken terminal are written to recovery files instead
+    pub fn run(&mut self) -> i32 {
+        let signal = Arc::new(AtomicUsize::new(0));
+        for number in [SIGTERM, SIGHUP] {
+            if let Err(e) = signal_hook::flag::register_usize(number, Arc::clone(&signal), number as usize) {
+                tracing::error!("could not handle signal {number}: {e}");
+            }
+        }
 
-            if let Some(InterfaceState::Quit(str)) = self.interface.handle_event(&event, ()) {
-                self.deinit();
-                if let Err(str) = str {
-                    eprintln!("{}", str);
-                    panic!();
+        match panic::catch_unwind(AssertUnwindSafe(|| self.run_loop(&signal))) {
+            Ok(Stop::Quit(result)) => {
+                self.interface.deinit();
+                // the tabs that could be saved are clean now, so only the others end up in recovery files
+                if let Err(e) = self.interface.save_all() {
+                    return self.emergency_exit(&format!("could not save: {e}"), 1);
+                }
+                match result {
+                    Ok(()) => 0,
+                    Err(e) => {
+                        eprintln!("{e}");
+                        1
+                    }
                 }
-                break;
             }
+            Ok(Stop::Signal(number)) => {
+                self.interface.deinit();
+                self.emergency_exit(&format!("stopped by signal {number}"), 128 + number)
+            }
+            Ok(Stop::Failed(e)) => {
+                self.interface.deinit();
+                self.emergency_exit(&format!("could not read from the terminal: {e}"), 1)
+            }
+            // the panic hook already gave the terminal back, doing it again moves the cursor over the panic
+            Err(_) => self.emergency_exit("crashed", 101),
         }
     }
 
-    pub fn deinit(&self) {
-        self.interface.deinit();
-
-        let mut tmp = String::default();
+    fn run_loop(&mut self, signal: &AtomicUsize) -> Stop {
+        self.interface.draw();
+        self.interface.flush();
+        loop {
+            // signals only set the flag, so it is checked between events
+            match signal.load(Ordering::Relaxed) {
+                0 => {}
+                number => return Stop::Signal(number as i32),
+            }
+            match poll(Duration::from_millis(100)) {
+                Ok(true) => {}
+                Ok(false) => continue,
+                Err(e) => return Stop::Failed(e.to_string()),
+            }
+            let event = match read() {
+                Ok(event) => event,
+                Err(e) => return Stop::Failed(e.to_string()),
+            };
 
-        for list in &self.interface.collection.lists {
-            tmp.push_str(&format!("{}:\n", list.title));
-            for todos in &list.data {
-                tmp.push_str(&format!("{} {}\n", todos.status, todos.data));
+            if let Some(InterfaceState::Quit(result)) = self.interface.handle_event(&event, ()) {
+                return Stop::Quit(result);
             }
+            self.interface.draw();
+            self.interface.flush();
         }
+    }
 
-        if !filesystem::write(&self.file, tmp) {
-            tracing::info!("failed to write to file {}", &self.file);
+    /// writes the unsaved edits to recovery files, the terminal has to be given back already
+    fn emergency_exit(&mut self, reason: &str, code: i32) -> i32 {
+        tracing::error!("{reason}");
+        eprintln!("redo {reason}");
+        for written in self.interface.emergency_save() {
+            match written {
+                Ok(path) => eprintln!(
+                    "unsaved edits were written to {}, they are offered back when the file is opened again",
+                    path.display()
+                ),
+                Err(e) => eprintln!("{e}"),
+            }
         }
+        code
     }
 }
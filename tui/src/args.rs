@@ -0,0 +1,159 @@
+use std::path::PathBuf;
+
+pub const USAGE: &str = "\
+usage: tui [options] [file]...
+       tui [file] --query <query>...
+
+every file is opened in its own tab, without one the default file is opened
+
+options:
+  -c, --config <file>    read the config from the file instead of the default one
+      --log-file <file>  write the log to the file
+  -r, --read-only        never write the files
+  -l, --list <name>      start in the list called name
+      --query <query>    print the todos matching the query instead of starting the tui
+  -h, --help             print this help
+  -V, --version          print the version
+";
+
+/// What the command line asks for.
+#[derive(Debug, Default)]
+pub struct Args {
+    /// every file is opened in a tab of its own
+    pub files: Vec<String>,
+    pub config: Option<PathBuf>,
+    pub log_file: Option<PathBuf>,
+    pub read_only: bool,
+    /// the list to start in
+    pub list: Option<String>,
+    /// print the todos matching the query instead of starting the tui
+    pub query: Option<String>,
+}
+
+pub enum Parsed {
+    Run(Args),
+    Help,
+    Version,
+}
+
+impl Args {
+    /// parses the arguments without the name of the program, `--flag value` and `--flag=value` are the same
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Parsed, String> {
+        let mut parsed = Args::default();
+        let mut args = args.into_iter();
+        let mut only_files = false;
+
+        while let Some(arg) = args.next() {
+            if only_files || !arg.starts_with('-') || arg == "-" {
+                parsed.files.push(arg);
+                continue;
+            }
+            let (flag, inline) = match arg.split_once('=') {
+                Some((flag, value)) if flag.starts_with("--") => (flag.to_string(), Some(value.to_string())),
+                _ => (arg, None),
+            };
+            let value = |args: &mut dyn Iterator<Item = String>| {
+                inline
+                    .clone()
+                    .or_else(|| args.next())
+                    .ok_or_else(|| format!("{flag} needs a value"))
+            };
+
+            match flag.as_str() {
+                "--" | "--help" | "--version" | "--read-only" if inline.is_some() => {
+                    return Err(format!("{flag} does not take a value"))
+                }
+                "--" => only_files = true,
+                "-h" | "--help" => return Ok(Parsed::Help),
+                "-V" | "--version" => return Ok(Parsed::Version),
+                "-c" | "--config" => parsed.config = Some(PathBuf::from(value(&mut args)?)),
+                "--log-file" => parsed.log_file = Some(PathBuf::from(value(&mut args)?)),
+                "-r" | "--read-only" => parsed.read_only = true,
+                "-l" | "--list" => parsed.list = Some(value(&mut args)?),
+                "--query" => {
+                    // the query is the rest of the line so it does not have to be quoted
+                    let query = inline.into_iter().chain(args.by_ref()).collect::<Vec<_>>();
+                    if query.is_empty() {
+                        return Err("--query needs a query".to_string());
+                    }
+                    parsed.query = Some(query.join(" "));
+                }
+                _ => return Err(format!("unknown option `{flag}`")),
+            }
+        }
+        Ok(Parsed::Run(parsed))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+
+    use super::{Args, Parsed};
+
+    fn parse(args: &[&str]) -> Result<Parsed, String> {
+        Args::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    fn run(args: &[&str]) -> Args {
+        match parse(args) {
+            Ok(Parsed::Run(args)) => args,
+            Ok(_) => panic!("{args:?} does not run the tui"),
+            Err(e) => panic!("{args:?} is invalid: {e}"),
+        }
+    }
+
+    #[test]
+    fn test_flags() {
+        let args = run(&["a.todo", "-r", "--config", "c.toml", "--list=work", "b.todo"]);
+        assert_eq!(args.files, ["a.todo", "b.todo"]);
+        assert!(args.read_only);
+        assert_eq!(args.config, Some(PathBuf::from("c.toml")));
+        assert_eq!(args.list.as_deref(), Some("work"));
+        assert_eq!(args.log_file, None);
+
+        let args = run(&["-l", "home", "--read-only", "--log-file=redo.log"]);
+        assert!(args.files.is_empty());
+        assert!(args.read_only);
+        assert_eq!(args.list.as_deref(), Some("home"));
+        assert_eq!(args.log_file, Some(PathBuf::from("redo.log")));
+        assert!(!run(&["a.todo"]).read_only);
+
+        assert!(matches!(parse(&["a.todo", "-h"]), Ok(Parsed::Help)));
+        assert!(matches!(parse(&["--version"]), Ok(Parsed::Version)));
+    }
+
+    #[test]
+    fn test_files_after_dashes() {
+        let args = run(&["a.todo", "--", "-r", "--help", "-"]);
+        assert_eq!(args.files, ["a.todo", "-r", "--help", "-"]);
+        assert!(!args.read_only);
+        assert_eq!(run(&["-"]).files, ["-"]);
+    }
+
+    #[test]
+    fn test_query() {
+        let args = run(&["a.todo", "--query", "status:open", "#work"]);
+        assert_eq!(args.files, ["a.todo"]);
+        assert_eq!(args.query.as_deref(), Some("status:open #work"));
+        // everything after it is part of the query, options too
+        assert_eq!(run(&["--query=due<7d", "-r"]).query.as_deref(), Some("due<7d -r"));
+    }
+
+    #[test]
+    fn test_errors() {
+        let error = |args: &[&str]| parse(args).err();
+        assert_eq!(
+            error(&["--frobnicate"]),
+            Some("unknown option `--frobnicate`".to_string())
+        );
+        assert_eq!(error(&["-x", "a.todo"]), Some("unknown option `-x`".to_string()));
+        assert_eq!(
+            error(&["--read-only=yes"]),
+            Some("--read-only does not take a value".to_string())
+        );
+        assert_eq!(error(&["a.todo", "-c"]), Some("-c needs a value".to_string()));
+        assert_eq!(error(&["--list"]), Some("--list needs a value".to_string()));
+        assert_eq!(error(&["--query"]), Some("--query needs a query".to_string()));
+    }
+}
@@ -0,0 +1,569 @@
+use std::path::Path;
+
+use crossterm::event::{Event, KeyCode};
+use ratatui::layout::{Position, Rect};
+use ratatui::text::Line;
+use ratatui::widgets::{Clear, Paragraph};
+use ratatui::Frame;
+use redo::export::Format;
+use redo::query::split;
+use redo::sort::SortKey;
+
+use crate::event::EventHandler;
+use crate::theme::Theme;
+
+/// A command typed into the `:` command line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    /// save, to another file when one is given
+    Write(Option<String>),
+    /// quit, with `!` unsaved changes are thrown away instead of refusing to
+    Quit(bool),
+    WriteQuit,
+    Sort(Vec<SortKey>),
+    /// show the todos sorted without reordering the file, in the order of the file when there are no keys
+    View(Vec<SortKey>),
+    /// show only the open todos that are not waiting for others, or every todo again
+    Actionable,
+    Rename(String),
+    Export(Format, String),
+    /// archive the completed todos, only those completed at least that many days ago when given
+    Archive(Option<u32>),
+    /// switch between the lists and their archives
+    Archived,
+    /// show or hide the lists with every todo done
+    Completed,
+    /// put the archived todo under the cursor, or every todo of the archive, back into its list
+    Restore,
+    /// open the file in a new tab, or go to its tab when it is already open
+    Open(String),
+    /// save and close the current tab
+    Close,
+}
+
+/// what the arguments of a command complete to
+#[derive(Debug, Clone, Copy)]
+enum Completion {
+    None,
+    Values(&'static [&'static str]),
+    Path,
+}
+
+struct Spec {
+    name: &'static str,
+    aliases: &'static [&'static str],
+    usage: &'static str,
+    /// the least and most amount of arguments
+    args: (usize, usize),
+    completions: &'static [Completion],
+}
+
+/// every command the command line knows
+const COMMANDS: [Spec; 14] = [
+    Spec {
+        name: "write",
+        aliases: &["w"],
+        usage: "write [file]",
+        args: (0, 1),
+        completions: &[Completion::Path],
+    },
+    Spec {
+        name: "quit",
+        aliases: &["q"],
+        usage: "quit[!]",
+        args: (0, 0),
+        completions: &[],
+    },
+    Spec {
+        name: "wq",
+        aliases: &["x"],
+        usage: "wq",
+        args: (0, 0),
+        completions: &[],
+    },
+    Spec {
+        name: "sort",
+        aliases: &[],
+        usage: "sort <key>...",
+        args: (1, SortKey::NAMES.len()),
+        completions: &[Completion::Values(&SortKey::NAMES)],
+    },
+    Spec {
+        name: "view",
+        aliases: &[],
+        usage: "view [key]...",
+        args: (0, SortKey::NAMES.len()),
+        completions: &[Completion::Values(&SortKey::NAMES)],
+    },
+    Spec {
+        name: "actionable",
+        aliases: &[],
+        usage: "actionable",
+        args: (0, 0),
+        completions: &[],
+    },
+    Spec {
+        name: "rename",
+        aliases: &[],
+        usage: "rename <name>",
+        args: (1, 1),
+        completions: &[Completion::None],
+    },
+    Spec {
+        name: "export",
+        aliases: &[],
+        usage: "export <format> <file>",
+        args: (2, 2),
+        completions: &[Completion::Values(&Format::NAMES), Completion::Path],
+    },
+    Spec {
+        name: "archive",
+        aliases: &[],
+        usage: "archive [days]",
+        args: (0, 1),
+        completions: &[Completion::None],
+    },
+    Spec {
+        name: "archived",
+        aliases: &[],
+        usage: "archived",
+        args: (0, 0),
+        completions: &[],
+    },
+    Spec {
+        name: "completed",
+        aliases: &[],
+        usage: "completed",
+        args: (0, 0),
+        completions: &[],
+    },
+    Spec {
+        name: "restore",
+        aliases: &[],
+        usage: "restore",
+        args: (0, 0),
+        completions: &[],
+    },
+    Spec {
+        name: "open",
+        aliases: &["e", "edit"],
+        usage: "open <file>",
+        args: (1, 1),
+        completions: &[Completion::Path],
+    },
+    Spec {
+        name: "close",
+        aliases: &["clo"],
+        usage: "close",
+        args: (0, 0),
+        completions: &[],
+    },
+];
+
+impl Spec {
+    fn find(name: &str) -> Option<&'static Spec> {
+        COMMANDS
+            .iter()
+            .find(|spec| spec.name == name || spec.aliases.contains(&name))
+    }
+
+    /// what the `nth` argument completes to, the last completion repeats for commands taking many arguments
+    fn completion(&self, nth: usize) -> Completion {
+        match self.args.1 {
+            0 => Completion::None,
+            _ => self
+                .completions
+                .get(nth)
+                .or(self.completions.last())
+                .copied()
+                .unwrap_or(Completion::None),
+        }
+    }
+}
+
+impl Command {
+    /// whether the command changes the collection
+    pub fn edits(&self) -> bool {
+        matches!(
+            self,
+            Command::Sort(_) | Command::Rename(_) | Command::Archive(_) | Command::Restore
+        )
+    }
+}
+
+impl std::str::FromStr for Command {
+    type Err = String;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let words = split(line)?;
+        let Some((name, args)) = words.split_first() else {
+            return Err("no command given".to_string());
+        };
+        // only quitting can be forced
+        let (spec, force) = match name.strip_suffix('!').map(Spec::find) {
+            Some(Some(spec)) if spec.name == "quit" => (spec, true),
+            Some(_) => return Err(format!("unknown command `{name}`")),
+            None => (
+                Spec::find(name).ok_or_else(|| format!("unknown command `{name}`"))?,
+                false,
+            ),
+        };
+        let (min, max) = spec.args;
+        if args.len() < min || args.len() > max {
+            return Err(format!("usage: :{}", spec.usage));
+        }
+
+        let command = match spec.name {
+            "write" => Command::Write(args.first().cloned()),
+            "quit" => Command::Quit(force),
+            "wq" => Command::WriteQuit,
+            "sort" => Command::Sort(args.iter().map(|key| key.parse()).collect::<Result<_, _>>()?),
+            "view" => Command::View(args.iter().map(|key| key.parse()).collect::<Result<_, _>>()?),
+            "actionable" => Command::Actionable,
+            "rename" => Command::Rename(args[0].clone()),
+            "export" => Command::Export(args[0].parse()?, args[1].clone()),
+            "archive" => match args.first() {
+                Some(days) => match days.parse() {
+                    Ok(days) => Command::Archive(Some(days)),
+                    Err(_) => return Err(format!("`{days}` is not an amount of days")),
+                },
+                None => Command::Archive(None),
+            },
+            "archived" => Command::Archived,
+            "completed" => Command::Completed,
+            "restore" => Command::Restore,
+            "open" => Command::Open(args[0].clone()),
+            "close" => Command::Close,
+            _ => unreachable!("every command in COMMANDS is parsed"),
+        };
+        Ok(command)
+    }
+}
+
+/// The `:` line at the bottom of the screen.
+#[derive(Debug, Default)]
+pub struct CommandLine {
+    pub active: bool,
+    pub input: String,
+
+    /// what tab cycles through, and the one that is filled in
+    completions: Vec<String>,
+    completion: Option<usize>,
+    /// the input from before completing, completions replace its last word
+    completed: String,
+    history: Vec<String>,
+    /// how far back in the history up went
+    history_idx: usize,
+}
+
+pub enum CommandLineState {
+    Run(String),
+    Cancel,
+}
+
+impl EventHandler<(), CommandLineState> for CommandLine {
+    fn handle_event(&mut self, event: &Event, _: ()) -> Option<CommandLineState> {
+        let Event::Key(key) = event else {
+            return None;
+        };
+        if !matches!(key.code, KeyCode::Tab | KeyCode::BackTab) {
+            self.completions.clear();
+            self.completion = None;
+        }
+
+        match key.code {
+            KeyCode::Char(ch) => self.input.push(ch),
+            KeyCode::Backspace if self.input.is_empty() => {
+                self.active = false;
+                return Some(CommandLineState::Cancel);
+            }
+            KeyCode::Backspace => _ = self.input.pop(),
+            KeyCode::Tab => self.complete(true),
+            KeyCode::BackTab => self.complete(false),
+            KeyCode::Up | KeyCode::Down => {
+                self.history_idx = match key.code {
+                    KeyCode::Up => usize::min(self.history_idx + 1, self.history.len()),
+                    _ => self.history_idx.saturating_sub(1),
+                };
+                self.input = match self.history_idx {
+                    0 => String::new(),
+                    idx => self.history[self.history.len() - idx].clone(),
+                };
+            }
+            KeyCode::Enter => {
+                self.active = false;
+                let input = std::mem::take(&mut self.input);
+                if !input.trim().is_empty() && self.history.last() != Some(&input) {
+                    self.history.push(input.clone());
+                }
+                return Some(CommandLineState::Run(input));
+            }
+            KeyCode::Esc => {
+                self.active = false;
+                return Some(CommandLineState::Cancel);
+            }
+            _ => {}
+        }
+        None
+    }
+}
+
+impl CommandLine {
+    pub fn open(&mut self) {
+        self.active = true;
+        self.input.clear();
+        self.completions.clear();
+        self.completion = None;
+        self.history_idx = 0;
+    }
+
+    /// fills in the next (or previous) completion of the word under the cursor
+    fn complete(&mut self, forward: bool) {
+        if self.completion.is_none() {
+            self.completed = self.input.clone();
+            self.completions = completions(&self.input);
+        }
+        let len = self.completions.len();
+        if len == 0 {
+            return;
+        }
+        let next = match (self.completion, forward) {
+            (None, true) => 0,
+            (None, false) => len - 1,
+            (Some(idx), true) => (idx + 1) % len,
+            (Some(idx), false) => (idx + len - 1) % len,
+        };
+        self.completion = Some(next);
+
+        let (_, start) = words(&self.completed);
+        let completion = &self.completions[next];
+        // quoted the way the command is split, so paths with spaces stay one argument
+        self.input = match completion.contains(char::is_whitespace) {
+            true => format!("{}\"{completion}\"", &self.completed[..start]),
+            false => format!("{}{completion}", &self.completed[..start]),
+        };
+        // a single completion is final, so the next argument can be typed right away
+        if len == 1 && !completion.ends_with('/') {
+            self.input.push(' ');
+            self.completions.clear();
+            self.completion = None;
+        }
+    }
+
+    pub fn draw(&self, frame: &mut Frame, area: Rect, theme: &Theme) {
+        let prompt = Rect {
+            y: area.bottom().saturating_sub(1),
+            height: u16::min(area.height, 1),
+            ..area
+        };
+        frame.render_widget(Clear, prompt);
+        frame.render_widget(Paragraph::new(format!(":{}", self.input)), prompt);
+        let x = prompt.x + 1 + self.input.chars().count() as u16;
+        frame.set_cursor_position(Position::new(u16::min(x, prompt.right().saturating_sub(1)), prompt.y));
+
+        // the other completions are listed above the prompt
+        if self.completions.len() > 1 && prompt.y > area.y {
+            let mut spans = vec![];
+            for (idx, completion) in self.completions.iter().enumerate() {
+                let style = match Some(idx) == self.completion {
+                    true => theme.selected,
+                    false => theme.popup,
+                };
+                spans.push(ratatui::text::Span::styled(format!(" {completion} "), style));
+            }
+            let list = Rect {
+                y: prompt.y - 1,
+                ..prompt
+            };
+            frame.render_widget(Clear, list);
+            frame.render_widget(Paragraph::new(Line::from(spans)), list);
+        }
+    }
+}
+
+/// the words of `input` split like commands are, with quotes left open while typing, and where the last word
+/// starts. Input ending in whitespace ends in an empty word that is still to be typed
+fn words(input: &str) -> (Vec<String>, usize) {
+    let mut words = vec![];
+    let mut word = None;
+    let mut start = input.len();
+    let mut quoted = false;
+    for (idx, ch) in input.char_indices() {
+        if ch.is_whitespace() && !quoted {
+            words.extend(word.take());
+            continue;
+        }
+        let word = word.get_or_insert_with(|| {
+            start = idx;
+            String::new()
+        });
+        match ch {
+            '"' => quoted = !quoted,
+            ch => word.push(ch),
+        }
+    }
+    match word {
+        Some(word) => words.push(word),
+        None => {
+            words.push(String::new());
+            start = input.len();
+        }
+    }
+    (words, start)
+}
+
+/// everything the last word of `input` can be completed to
+fn completions(input: &str) -> Vec<String> {
+    let (words, _) = words(input);
+    let nth = words.len() - 1;
+    let prefix = words[nth].as_str();
+
+    if nth == 0 {
+        return COMMANDS
+            .iter()
+            .map(|spec| spec.name)
+            .filter(|name| name.starts_with(prefix))
+            .map(String::from)
+            .collect();
+    }
+    let Some(spec) = Spec::find(&words[0]) else {
+        return vec![];
+    };
+    match spec.completion(nth - 1) {
+        Completion::None => vec![],
+        Completion::Values(values) => values
+            .iter()
+            .filter(|value| value.starts_with(prefix))
+            .map(|value| value.to_string())
+            .collect(),
+        Completion::Path => complete_path(prefix),
+    }
+}
+
+/// the files and directories starting with `prefix`, directories end in `/`
+fn complete_path(prefix: &str) -> Vec<String> {
+    let (dir, name) = match prefix.rfind('/') {
+        Some(idx) => (&prefix[..=idx], &prefix[idx + 1..]),
+        None => ("", prefix),
+    };
+    let read_from = match dir {
+        "" => Path::new("."),
+        dir => Path::new(dir),
+    };
+    let Ok(entries) = std::fs::read_dir(read_from) else {
+        return vec![];
+    };
+
+    let mut paths = entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let file_name = entry.file_name().into_string().ok()?;
+            // hidden files only show up once their name is started
+            if !file_name.starts_with(name) || (file_name.starts_with('.') && !name.starts_with('.')) {
+                return None;
+            }
+            let slash = match entry.file_type().is_ok_and(|kind| kind.is_dir()) {
+                true => "/",
+                false => "",
+            };
+            Some(format!("{dir}{file_name}{slash}"))
+        })
+        .collect::<Vec<_>>();
+    paths.sort();
+    paths
+}
+
+/// What a command or a key did, shown in the status bar until the next key press.
+#[derive(Debug, Clone)]
+pub struct Message {
+    pub text: String,
+    pub error: bool,
+}
+
+impl Message {
+    pub fn info(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            error: false,
+        }
+    }
+
+    pub fn error(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            error: true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use redo::export::Format;
+    use redo::sort::SortKey;
+
+    use super::{completions, Command, CommandLine};
+
+    #[test]
+    fn test_parse() {
+        assert_eq!("w".parse(), Ok(Command::Write(None)));
+        assert_eq!("write a.todo".parse(), Ok(Command::Write(Some("a.todo".to_string()))));
+        assert_eq!(
+            "w \"my todos.todo\"".parse(),
+            Ok(Command::Write(Some("my todos.todo".to_string())))
+        );
+        assert_eq!("q".parse(), Ok(Command::Quit(false)));
+        assert_eq!("quit!".parse(), Ok(Command::Quit(true)));
+        assert_eq!(
+            "sort status due".parse(),
+            Ok(Command::Sort(vec![SortKey::Status, SortKey::Due]))
+        );
+        assert_eq!("view".parse(), Ok(Command::View(vec![])));
+        assert_eq!(
+            "export json out.json".parse(),
+            Ok(Command::Export(Format::Json, "out.json".to_string()))
+        );
+        assert_eq!("archive 7".parse(), Ok(Command::Archive(Some(7))));
+        assert_eq!("e a.todo".parse(), Ok(Command::Open("a.todo".to_string())));
+
+        assert!("w!".parse::<Command>().is_err());
+        assert!("q a.todo".parse::<Command>().is_err());
+        assert!("sort".parse::<Command>().is_err());
+        assert!("sort size".parse::<Command>().is_err());
+        assert!("archive soon".parse::<Command>().is_err());
+        assert!("rename \"unclosed".parse::<Command>().is_err());
+        assert!("frobnicate".parse::<Command>().is_err());
+    }
+
+    #[test]
+    fn test_completions() {
+        assert_eq!(completions("").len(), 14);
+        assert_eq!(completions("ar"), vec!["archive", "archived"]);
+        assert_eq!(completions("sort status t"), vec!["text"]);
+        assert_eq!(completions("export "), vec!["todo", "json", "markdown"]);
+        assert_eq!(completions("quit "), Vec::<String>::new());
+        assert_eq!(completions("frobnicate "), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_complete_path() {
+        let dir = std::env::temp_dir().join(format!("redo-complete-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).expect("temporary directory");
+        std::fs::write(dir.join("my todos.todo"), "").expect("temporary file");
+        let dir = dir.display().to_string();
+
+        let path = format!("{dir}/my todos.todo");
+        assert_eq!(completions(&format!("open {dir}/my")), vec![path.clone()]);
+        // the quote of an earlier completion is still open while typing
+        assert_eq!(completions(&format!("open \"{dir}/my t")), vec![path.clone()]);
+
+        let mut line = CommandLine {
+            input: format!("open {dir}/m"),
+            ..Default::default()
+        };
+        line.complete(true);
+        assert_eq!(line.input, format!("open \"{path}\" "));
+        assert_eq!(line.input.trim().parse(), Ok(Command::Open(path)));
+
+        std::fs::remove_dir_all(dir).expect("temporary directory removed");
+    }
+}
@@ -0,0 +1,152 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use redo::sort::SortKey;
+use serde::Deserialize;
+
+use crate::keymap::{parse_keys, Action, Keymap, Scope};
+use crate::theme::{Theme, ThemeFile};
+
+#[derive(Debug, Default, Clone)]
+pub struct Config {
+    pub keymap: Keymap,
+    pub theme: Theme,
+    /// how the editor sorts todos, without reordering the file
+    pub sort: Vec<SortKey>,
+    /// whether lists with every todo done are left out of the selection bar
+    pub hide_completed: bool,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct ConfigFile {
+    keys: KeysFile,
+    theme: ThemeFile,
+    view: ViewFile,
+}
+
+/// `[view]`, how the todos are shown
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct ViewFile {
+    sort: Vec<String>,
+    hide_completed: bool,
+}
+
+/// `[keys.<scope>]` tables mapping action names to one or more key sequences
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct KeysFile {
+    global: BTreeMap<String, KeyList>,
+    normal: BTreeMap<String, KeyList>,
+    visual: BTreeMap<String, KeyList>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum KeyList {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl KeyList {
+    fn into_vec(self) -> Vec<String> {
+        match self {
+            KeyList::One(keys) => vec![keys],
+            KeyList::Many(keys) => keys,
+        }
+    }
+}
+
+impl Config {
+    /// loads the config at `path`, a missing file gives the default config
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content = match std::fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(format!("could not read {}: {e}", path.display())),
+        };
+        Self::parse(&content).map_err(|e| format!("{}: {e}", path.display()))
+    }
+
+    pub fn parse(content: &str) -> Result<Self, String> {
+        let file: ConfigFile = toml::from_str(content).map_err(|e| e.to_string())?;
+
+        let mut keymap = Keymap::default();
+        let scopes = [
+            (Scope::Global, file.keys.global),
+            (Scope::Normal, file.keys.normal),
+            (Scope::Visual, file.keys.visual),
+        ];
+        for (scope, bindings) in scopes {
+            for (name, keys) in bindings {
+                let action = name.parse::<Action>()?;
+                let keys = keys
+                    .into_vec()
+                    .iter()
+                    .map(|keys| parse_keys(keys))
+                    .collect::<Result<Vec<_>, _>>()?;
+                keymap.bind(scope, action, keys);
+            }
+        }
+
+        let conflicts = keymap.conflicts();
+        if !conflicts.is_empty() {
+            return Err(format!("conflicting key bindings:\n  {}", conflicts.join("\n  ")));
+        }
+
+        let theme = Theme::from_file(file.theme)?;
+        let sort = file.view.sort.iter().map(|key| key.parse()).collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            keymap,
+            theme,
+            sort,
+            hide_completed: file.view.hide_completed,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use redo::sort::SortKey;
+
+    use super::Config;
+    use crate::keymap::{Action, Scope};
+
+    #[test]
+    fn test_parse() {
+        let config = Config::parse(
+            r#"
+            [keys.normal]
+            down = ["j", "<C-n>"]
+            up = "<C-k>"
+
+            [view]
+            sort = ["status", "due"]
+            hide_completed = true
+            "#,
+        );
+        let config = config.expect("valid config");
+        assert_eq!(config.keymap.keys(Scope::Normal, Action::Down), vec!["j", "<C-n>"]);
+        assert_eq!(config.keymap.keys(Scope::Normal, Action::Up), vec!["<C-k>"]);
+        // only the scope that was given changes
+        assert_eq!(config.keymap.keys(Scope::Visual, Action::Up), vec!["k", "<Up>"]);
+        assert_eq!(config.sort, vec![SortKey::Status, SortKey::Due]);
+        assert!(config.hide_completed);
+
+        let config = Config::parse("").expect("empty config");
+        assert!(config.sort.is_empty());
+        assert!(!config.hide_completed);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = |content| Config::parse(content).expect_err("invalid config");
+        assert_eq!(error("[keys.normal]\njump = \"J\""), "unknown action `jump`");
+        assert!(error("[keys.insert]\ndown = \"j\"").contains("unknown field `insert`"));
+        assert!(error("[keys.normal]\ndown = \"<Nope>\"").contains("unknown key `<Nope>`"));
+        assert!(error("[keys.normal]\ndown = \"g\"").starts_with("conflicting key bindings:"));
+        assert!(error("[view]\nsort = [\"size\"]").contains("size"));
+    }
+}
@@ -18,4 +18,90 @@ impl Cursor {
     pub fn new(x: u16, y: u16) -> Self {
         Self { y, x }
     }
+
+    /// moves the cursor to `line` of the underlying data, adjusting `scroll` so the line stays within the
+    /// `height` visible lines
+    pub fn scroll_to(&mut self, line: u16, scroll: &mut u16, height: u16) {
+        let height = height.max(1);
+        if line < *scroll {
+            *scroll = line;
+        } else if line >= *scroll + height {
+            *scroll = line + 1 - height;
+        }
+        self.y = line - *scroll;
+    }
+
+    /// keeps the cursor on one of the `len` lines and the view as full as the lines allow after `height` or `len`
+    /// changed
+    pub fn clamp(&mut self, scroll: &mut u16, height: u16, len: u16) {
+        let line = u16::min(*scroll + self.y, len.saturating_sub(1));
+        *scroll = u16::min(*scroll, len.saturating_sub(height.max(1)));
+        self.scroll_to(line, scroll, height);
+    }
+
+    /// scrolls the view of `len` lines by `amount`, down when it is positive, the cursor stays on its line until the
+    /// line goes out of view
+    pub fn scroll_by(&mut self, amount: i32, scroll: &mut u16, height: u16, len: u16) {
+        let height = height.max(1);
+        let line = *scroll + self.y;
+        let last_scroll = len.saturating_sub(height);
+        *scroll = (*scroll as i32 + amount).clamp(0, last_scroll as i32) as u16;
+        let last_line = u16::min(*scroll + height - 1, len.saturating_sub(1)).max(*scroll);
+        self.y = line.clamp(*scroll, last_line) - *scroll;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Cursor;
+
+    #[test]
+    fn test_scroll_to() {
+        let mut cursor = Cursor::default();
+        let mut scroll = 0;
+        cursor.scroll_to(12, &mut scroll, 10);
+        assert_eq!((scroll, cursor.y), (3, 9));
+        cursor.scroll_to(1, &mut scroll, 10);
+        assert_eq!((scroll, cursor.y), (1, 0));
+        // a pane without room still shows the line of the cursor
+        cursor.scroll_to(5, &mut scroll, 0);
+        assert_eq!((scroll, cursor.y), (5, 0));
+    }
+
+    #[test]
+    fn test_clamp() {
+        // line 25 of 30 with 10 lines showing
+        let mut cursor = Cursor::new(0, 5);
+        let mut scroll = 20;
+        // growing pulls the view back up so it stays full
+        cursor.clamp(&mut scroll, 20, 30);
+        assert_eq!((scroll, cursor.y), (10, 15));
+        // shrinking keeps the cursor in view
+        cursor.clamp(&mut scroll, 4, 30);
+        assert_eq!((scroll, cursor.y), (22, 3));
+        // removing lines moves the cursor onto the last one left
+        cursor.clamp(&mut scroll, 4, 8);
+        assert_eq!((scroll, cursor.y), (4, 3));
+        cursor.clamp(&mut scroll, 4, 0);
+        assert_eq!((scroll, cursor.y), (0, 0));
+    }
+
+    #[test]
+    fn test_scroll_by() {
+        let mut cursor = Cursor::new(0, 2);
+        let mut scroll = 0;
+        cursor.scroll_by(3, &mut scroll, 10, 30);
+        assert_eq!((scroll, cursor.y), (3, 0));
+        cursor.scroll_by(100, &mut scroll, 10, 30);
+        assert_eq!((scroll, cursor.y), (20, 0));
+        // the cursor stays on its line while it is in view
+        cursor.y = 4;
+        cursor.scroll_by(-2, &mut scroll, 10, 30);
+        assert_eq!((scroll, cursor.y), (18, 6));
+        cursor.scroll_by(-100, &mut scroll, 10, 30);
+        assert_eq!((scroll, cursor.y), (0, 9));
+        // fewer lines than fit do not scroll
+        cursor.scroll_by(5, &mut scroll, 10, 4);
+        assert_eq!((scroll, cursor.y), (0, 3));
+    }
 }
@@ -1,23 +1,55 @@
-use crossterm::event::{Event, KeyCode};
-use ratatui::layout::{Margin, Rect};
-use ratatui::style::{Style, Stylize};
-use ratatui::text::Line;
-use ratatui::widgets::{Block, Clear, List, ListDirection, Paragraph};
+use std::ops::Range;
+
+use crossterm::event::{Event, KeyCode, KeyEvent};
+use ratatui::layout::{Margin, Position, Rect};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Clear, List, ListDirection, ListItem, Paragraph};
 use ratatui::Frame;
+use redo::deps::Dependencies;
+use redo::todo::{Todo, TodoStatus};
 use redo::TodoList;
 
 use crate::cursor::{Cursor, CursorMovement};
 use crate::event::EventHandler;
+use crate::keymap::Action;
+use crate::modal::{Input, KeySequence, Mode};
+use crate::search::Search;
+use crate::theme::Theme;
 use crate::viewport::Viewport;
 
 #[derive(Debug, Default, Clone)]
 pub struct Editor {
     pub buffer: String,
     pub cursor: Cursor,
-    pub viewport: Viewport,
+    /// the size inside the borders
+    viewport: Viewport,
     pub popup_mode: bool,
+    pub keys: KeySequence,
+    /// the todos can not be changed
+    pub read_only: bool,
 
+    prompt: Prompt,
+    /// the todos the prompt acts on once it is confirmed
+    prompt_lines: Vec<usize>,
     scroll: u16,
+    /// the todos shown, in the order they are shown in, while the list is searched or sorted, `None` shows
+    /// every todo in the order of the file
+    rows: Option<Vec<usize>>,
+    /// the row visual mode was started on
+    anchor: usize,
+    register: Vec<Todo>,
+    /// the text typed into the last line opened with `o` or `O`, used by `.`
+    last_insert: String,
+    opened: bool,
+}
+
+/// what the text typed into the popup is used for
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+enum Prompt {
+    #[default]
+    Add,
+    Move,
+    Tag,
 }
 
 // #[allow(dead_code] because parts of the enum are detected as "unused" but theyre used in tui.rs
@@ -30,100 +62,146 @@ pub enum EditorState {
     Selected,
     Add(String),
     Remove(usize),
+    /// move the todos to the list with the given name
+    Move(Vec<usize>, String),
+    Undo(u16),
+    Redo(u16),
+    Search,
+    NextMatch(u16),
+    PrevMatch(u16),
+    /// the todos were edited in a way the search filter would hide
+    ClearSearch,
+    Command,
+    /// show the notes of the todo
+    Notes(usize),
+    /// edit the todo in `$EDITOR`
+    External(usize),
+    /// go to the next tab, or to the tab with that number
+    NextTab(Option<u16>),
+    PrevTab(u16),
+    /// the action would change a list that is read-only
+    ReadOnly,
 }
 
 impl EventHandler<&mut TodoList, EditorState> for Editor {
     fn handle_event(&mut self, event: &Event, list: &mut TodoList) -> Option<EditorState> {
-        if self.popup_mode {
-            if let Event::Key(key) = event {
-                match key.code {
-                    KeyCode::Esc => {
-                        self.popup_mode = false;
-                        self.buffer.clear();
-                        return Some(EditorState::DelPopup);
-                    }
-                    KeyCode::Char(ch) => self.push_char(ch),
-                    KeyCode::Backspace => _ = self.buffer.pop(),
-                    KeyCode::Enter => {
-                        self.popup_mode = false;
-                        let title = self.buffer.clone();
-                        self.buffer.clear();
-                        return Some(EditorState::Add(title));
-                    }
-
-                    _ => {}
-                };
-            }
-        }
+        let Event::Key(key) = event else {
+            return None;
+        };
 
-        if let Event::Key(key) = event {
+        if self.popup_mode {
             match key.code {
                 KeyCode::Esc => {
-                    self.cursor = Cursor::new(0, 0);
-                    return Some(EditorState::None);
-                }
-
-                KeyCode::Up | KeyCode::Char('k') => self.move_up(1),
-                KeyCode::Down | KeyCode::Char('j') => {
-                    self.move_down(1, list.len() as u16);
-                }
-                KeyCode::Left | KeyCode::Char('h') => self.move_left(1),
-                KeyCode::Right | KeyCode::Char('l') => {
-                    let max = list.len_line(self.cursor.y as usize);
-                    self.move_right(1, max as u16);
-                    tracing::info!(max);
+                    self.popup_mode = false;
+                    self.buffer.clear();
+                    return Some(EditorState::DelPopup);
                 }
-
+                KeyCode::Char(ch) => self.push_char(ch),
+                KeyCode::Backspace => _ = self.buffer.pop(),
                 KeyCode::Enter => {
-                    self.popup_mode = true;
-                    return Some(EditorState::AddPopup);
-                }
-                KeyCode::Char('x') => {
-                    let state = Some(EditorState::Remove(self.cursor.y as usize));
-                    return state;
-                }
-
-                KeyCode::Char(' ') => {
-                    if let Some(todo) = list.data.get_mut(self.cursor.y as usize) {
-                        todo.status.toggle()
-                    }
+                    self.popup_mode = false;
+                    let buffer = std::mem::take(&mut self.buffer);
+                    let lines = std::mem::take(&mut self.prompt_lines);
+                    return match self.prompt {
+                        Prompt::Add => Some(EditorState::Add(buffer)),
+                        Prompt::Move => Some(EditorState::Move(lines, buffer)),
+                        Prompt::Tag => {
+                            for line in lines {
+                                let todo = &mut list.data[line];
+                                let len = todo.len();
+                                todo.add_tag(&buffer);
+                                if todo.len() != len {
+                                    todo.touch();
+                                }
+                            }
+                            Some(EditorState::DelPopup)
+                        }
+                    };
                 }
 
                 _ => {}
+            };
+            return None;
+        }
+
+        let mode = self.keys.mode();
+        match self.keys.feed(key)? {
+            Input::Text(key) => {
+                self.insert_key(key, list);
+                None
             }
+            Input::Action {
+                action,
+                count,
+                repeated,
+            } => self.run_action(action, count, repeated, mode, list),
         }
-        None
     }
 }
 
 impl Editor {
-    pub fn draw(&mut self, frame: &mut Frame, editor_area: Rect, list: Option<&TodoList>) {
+    pub fn draw(
+        &mut self,
+        frame: &mut Frame,
+        editor_area: Rect,
+        list: Option<&TodoList>,
+        dependencies: &Dependencies,
+        search: &Search,
+        theme: &Theme,
+    ) {
+        let selection = self.selection();
         let mut todos_vec = vec![];
         if let Some(list) = list {
-            for item in list
-                .data
-                .iter()
-                .skip(self.scroll as usize)
-                .take(self.viewport.y() as usize)
-            {
-                todos_vec.push(item.status.to_string() + " " + &item.data);
+            for row in (self.scroll as usize..self.row_count(list.len())).take(self.height() as usize) {
+                let item = &list.data[self.line_at(row)];
+                let blockers = match item.status {
+                    TodoStatus::Complete => vec![],
+                    TodoStatus::Incomplete => dependencies.blockers(item),
+                };
+                let mut style = match item.status {
+                    TodoStatus::Complete => theme.completed,
+                    TodoStatus::Incomplete if !blockers.is_empty() => theme.blocked,
+                    TodoStatus::Incomplete => theme.todo,
+                };
+                if selection.as_ref().is_some_and(|range| range.contains(&row)) {
+                    style = theme.selected;
+                }
+                let mut line = search.highlight(&item.data, style, theme.matched);
+                line.spans.insert(0, Span::styled(item.status.to_string() + " ", style));
+                if !item.note.is_empty() {
+                    line.spans.push(Span::styled(" ✎", style));
+                }
+                if !blockers.is_empty() {
+                    line.spans.push(Span::styled(
+                        format!("  blocked by {}", blockers.join(", ")),
+                        theme.blocked,
+                    ));
+                }
+                todos_vec.push(ListItem::new(line));
             }
         };
 
-        let title = Line::from("Selection").style(Style::default().yellow());
-        let todos = List::new(todos_vec)
-            .direction(ListDirection::TopToBottom)
-            .blue()
-            .block(Block::bordered().white().title_top(title));
+        let title = Line::from("Selection").style(theme.title);
+        let mode = Line::from(format!(" {} {}", self.keys.mode(), self.keys.pending()));
+        let mut block = Block::bordered()
+            .border_style(theme.editor_border)
+            .title_top(title)
+            .title_bottom(mode);
+        if self.read_only {
+            block = block.title_bottom(Line::styled(" read-only ", theme.error).right_aligned());
+        }
+        let todos = List::new(todos_vec).direction(ListDirection::TopToBottom).block(block);
         frame.render_widget(todos, editor_area);
     }
 
-    pub fn draw_popup(&self, frame: &mut Frame) {
+    pub fn draw_popup(&self, frame: &mut Frame, theme: &Theme) {
         if self.popup_mode {
-            let popup = Block::bordered()
-                .style(Style::default())
-                .green()
-                .title_top("Adding Todo");
+            let title = match self.prompt {
+                Prompt::Add => "Adding Todo",
+                Prompt::Move => "Move To List",
+                Prompt::Tag => "Tag Todos",
+            };
+            let popup = Block::bordered().style(theme.popup).title_top(title);
             let text = Paragraph::new(&*self.buffer).block(popup);
 
             let area = frame.area().inner(Margin {
@@ -149,26 +227,405 @@ impl Editor {
     pub fn cursor_pos(&self) -> (u16, u16) {
         (self.cursor.y, self.cursor.x)
     }
+
+    /// the row of the view the cursor is on
+    pub fn row(&self) -> usize {
+        (self.scroll + self.cursor.y) as usize
+    }
+
+    /// the index of the todo under the cursor
+    pub fn line(&self) -> usize {
+        self.line_at(self.row())
+    }
+
+    /// drops the popup and what was typed or selected, back in normal mode
+    pub fn reset(&mut self) {
+        self.popup_mode = false;
+        self.buffer.clear();
+        self.prompt_lines.clear();
+        self.opened = false;
+        self.keys.set_mode(Mode::Normal);
+    }
+
+    /// whether `event` could change the list, confirming a prompt adds, moves or tags todos
+    pub fn may_change(&self, event: &Event) -> bool {
+        match event {
+            Event::Key(key) if self.popup_mode => key.code == KeyCode::Enter,
+            Event::Key(key) => self.keys.may_change(key),
+            _ => false,
+        }
+    }
+
+    /// whether keys are currently being typed as text rather than interpreted as commands
+    pub fn is_typing(&self) -> bool {
+        self.popup_mode || self.keys.mode() == Mode::Insert
+    }
+
+    /// the rows covered by visual mode
+    pub fn selection(&self) -> Option<Range<usize>> {
+        if self.keys.mode() != Mode::Visual {
+            return None;
+        }
+        let row = self.row();
+        Some(usize::min(self.anchor, row)..usize::max(self.anchor, row) + 1)
+    }
+
+    /// moves the cursor to the todo at index `line` of a list with `len` todos
+    pub fn goto(&mut self, line: usize, len: usize) {
+        let row = match &self.rows {
+            Some(rows) => rows
+                .iter()
+                .position(|idx| *idx == line)
+                .or_else(|| rows.iter().position(|idx| *idx > line))
+                .unwrap_or(rows.len()),
+            None => line,
+        };
+        self.goto_row(row, len);
+    }
+
+    /// only shows the todos at `rows`, keeping the cursor inside of them
+    pub fn set_rows(&mut self, rows: Option<Vec<usize>>, len: usize) {
+        let line = self.line();
+        self.rows = rows;
+        self.goto(line, len);
+    }
+
+    fn goto_row(&mut self, row: usize, len: usize) {
+        let row = usize::min(row, self.row_count(len).saturating_sub(1)) as u16;
+        let height = self.height();
+        self.cursor.scroll_to(row, &mut self.scroll, height);
+    }
+
+    fn line_at(&self, row: usize) -> usize {
+        match &self.rows {
+            // rows past the filter do not exist in the list either
+            Some(rows) => rows.get(row).copied().unwrap_or(usize::MAX),
+            None => row,
+        }
+    }
+
+    /// the amount of todos shown of a list with `len` todos
+    pub fn row_count(&self, len: usize) -> usize {
+        match &self.rows {
+            Some(rows) => rows.len(),
+            None => len,
+        }
+    }
+
+    /// the row of the todos of a list with `len` todos at `position` when the editor is drawn in `area`, with the
+    /// column inside the row
+    pub fn row_at(&self, area: Rect, position: Position, len: usize) -> Option<(usize, u16)> {
+        let inner = area.inner(Margin::new(1, 1));
+        if !inner.contains(position) {
+            return None;
+        }
+        let row = (self.scroll + position.y - inner.y) as usize;
+        (row < self.row_count(len)).then_some((row, position.x - inner.x))
+    }
+
+    /// puts the cursor at `column` of `row`, clicking the box in front of the todo toggles it instead
+    pub fn click(&mut self, row: usize, column: u16, list: &mut TodoList) -> Option<EditorState> {
+        self.goto_row(row, list.len());
+        let line = self.line();
+        let padding = 4; // padding is `[ ] `
+        if column >= padding - 1 {
+            let max = list.len_line(line).saturating_sub(1) as u16;
+            self.cursor.x = u16::min(column.saturating_sub(padding), max);
+            return None;
+        }
+        if self.read_only {
+            return Some(EditorState::ReadOnly);
+        }
+        let done = list.data[line].status == TodoStatus::Complete;
+        list.set_status(line, TodoStatus::from(!done));
+        None
+    }
+
+    /// scrolls the todos of a list with `len` todos by `amount` lines, down when it is positive
+    pub fn scroll_by(&mut self, amount: i32, len: usize) {
+        let height = self.height();
+        let rows = self.row_count(len) as u16;
+        self.cursor.scroll_by(amount, &mut self.scroll, height, rows);
+    }
+
+    /// fits the editor into `area`, keeping the cursor on one of the todos of a list with `len` todos in view
+    pub fn resize(&mut self, area: Rect, len: usize) {
+        let inner = area.inner(Margin::new(1, 1));
+        self.viewport = Viewport::new(inner.height, inner.width);
+        let height = self.height();
+        let rows = self.row_count(len) as u16;
+        self.cursor.clamp(&mut self.scroll, height, rows);
+    }
+
+    /// the amount of todos that fit inside the borders of the editor
+    fn height(&self) -> u16 {
+        self.viewport.y()
+    }
+
+    /// the amount of characters that fit inside the borders of the editor
+    pub fn width(&self) -> u16 {
+        self.viewport.x()
+    }
+
+    fn run_action(
+        &mut self,
+        action: Action,
+        count: Option<u16>,
+        repeated: bool,
+        mode: Mode,
+        list: &mut TodoList,
+    ) -> Option<EditorState> {
+        let amount = count.unwrap_or(1);
+        let line = self.line();
+        if self.read_only && action.edits() {
+            return Some(EditorState::ReadOnly);
+        }
+
+        match action {
+            Action::Up => self.move_up(amount),
+            Action::Down => self.move_down(amount, self.row_count(list.len()) as u16),
+            Action::Left => self.move_left(amount),
+            Action::Right => {
+                if line < list.len() {
+                    let max = list.len_line(line);
+                    self.move_right(amount, max as u16);
+                }
+            }
+            Action::Top => self.goto_row(count.unwrap_or(1).saturating_sub(1) as usize, list.len()),
+            Action::Bottom => {
+                let row = count.map(|count| count.saturating_sub(1) as usize);
+                self.goto_row(row.unwrap_or(usize::MAX), list.len());
+            }
+            Action::LineStart => self.cursor.x = 0,
+            Action::LineEnd => {
+                if line < list.len() {
+                    self.cursor.x = list.len_line(line).saturating_sub(1) as u16;
+                }
+            }
+
+            Action::Insert | Action::Append => {
+                if line >= list.len() {
+                    return None;
+                }
+                if action == Action::Append {
+                    self.cursor.x = list.len_line(line) as u16;
+                }
+                self.opened = false;
+                self.keys.set_mode(Mode::Insert);
+            }
+            Action::OpenBelow | Action::OpenAbove => {
+                let idx = match action {
+                    Action::OpenBelow if !list.is_empty() => line.saturating_add(1),
+                    _ => line,
+                };
+                let idx = usize::min(idx, list.len());
+                // the new todo would be hidden by the search while typing it
+                let filtered = self.rows.take().is_some();
+                let data = match repeated {
+                    true => self.last_insert.clone(),
+                    false => String::new(),
+                };
+                list.data.insert(idx, Todo::new(data));
+                self.goto(idx, list.len());
+                self.cursor.x = 0;
+                if !repeated {
+                    self.opened = true;
+                    self.keys.set_mode(Mode::Insert);
+                }
+                if filtered {
+                    return Some(EditorState::ClearSearch);
+                }
+            }
+
+            Action::Visual => {
+                if self.row_count(list.len()) == 0 {
+                    self.keys.set_mode(Mode::Normal);
+                }
+                self.anchor = self.row();
+            }
+            Action::DeleteLine => {
+                let lines = self.lines(mode, amount, list.len());
+                let first = lines.first().copied()?;
+                self.register = lines.iter().map(|line| list.data[*line].clone()).collect();
+                for line in lines.iter().rev() {
+                    list.data.remove(*line);
+                }
+                if let Some(rows) = &mut self.rows {
+                    // keep the remaining rows pointing at the same todos
+                    rows.retain(|row| !lines.contains(row));
+                    for row in rows.iter_mut() {
+                        *row -= lines.iter().filter(|line| **line < *row).count();
+                    }
+                }
+                self.goto(first, list.len());
+                self.cursor.x = 0;
+                if list.is_empty() {
+                    return Some(EditorState::None);
+                }
+            }
+            Action::Yank => {
+                let lines = self.lines(mode, amount, list.len());
+                self.register = lines.iter().map(|line| list.data[*line].clone()).collect();
+                if let Some(first) = lines.first() {
+                    self.goto(*first, list.len());
+                }
+            }
+            Action::Paste | Action::PasteAbove => {
+                if self.register.is_empty() {
+                    return None;
+                }
+                let idx = match action {
+                    Action::Paste if !list.is_empty() => line.saturating_add(1),
+                    _ => line,
+                };
+                let idx = usize::min(idx, list.len());
+                for _ in 0..amount {
+                    list.data.splice(idx..idx, self.register.iter().cloned());
+                }
+                let filtered = self.rows.take().is_some();
+                self.goto(idx, list.len());
+                if filtered {
+                    return Some(EditorState::ClearSearch);
+                }
+            }
+            Action::Toggle => {
+                let lines = self.lines(mode, amount, list.len());
+                // everything gets completed unless it already is, so mixed selections end up the same
+                let done = lines.iter().all(|line| list.data[*line].status == TodoStatus::Complete);
+                // from the last todo to the first since recurring todos add their next occurrence after them
+                for line in lines.into_iter().rev() {
+                    list.set_status(line, TodoStatus::from(!done));
+                }
+            }
+            Action::Remove => {
+                if line < list.len() {
+                    return Some(EditorState::Remove(line));
+                }
+            }
+
+            Action::Move | Action::Tag => {
+                let lines = self.lines(mode, amount, list.len());
+                let first = lines.first().copied()?;
+                let prompt = match action {
+                    Action::Move => Prompt::Move,
+                    _ => Prompt::Tag,
+                };
+                self.goto(first, list.len());
+                return Some(self.open_prompt(prompt, lines));
+            }
+            Action::Undo => return Some(EditorState::Undo(amount)),
+            Action::Redo => return Some(EditorState::Redo(amount)),
+            Action::Search => return Some(EditorState::Search),
+            Action::NextMatch => return Some(EditorState::NextMatch(amount)),
+            Action::PrevMatch => return Some(EditorState::PrevMatch(amount)),
+            Action::Command => return Some(EditorState::Command),
+            Action::NextTab => return Some(EditorState::NextTab(count)),
+            Action::PrevTab => return Some(EditorState::PrevTab(amount)),
+            Action::Notes | Action::External => {
+                if line < list.len() {
+                    return match action {
+                        Action::Notes => Some(EditorState::Notes(line)),
+                        _ => Some(EditorState::External(line)),
+                    };
+                }
+            }
+
+            Action::Confirm => return Some(self.open_prompt(Prompt::Add, vec![])),
+            Action::Cancel => {
+                if mode == Mode::Normal {
+                    self.cursor = Cursor::new(0, 0);
+                    self.scroll = 0;
+                    return Some(EditorState::None);
+                }
+                self.finish_insert(list);
+            }
+            Action::Repeat | Action::Quit | Action::Help | Action::Finder => {}
+        }
+        None
+    }
+
+    fn open_prompt(&mut self, prompt: Prompt, lines: Vec<usize>) -> EditorState {
+        self.prompt = prompt;
+        self.prompt_lines = lines;
+        self.popup_mode = true;
+        EditorState::AddPopup
+    }
+
+    /// the todos a linewise action acts on, the visual selection or `amount` rows starting at the cursor, in the
+    /// order of the list
+    fn lines(&self, mode: Mode, amount: u16, len: usize) -> Vec<usize> {
+        let row = self.row();
+        let rows = match mode {
+            Mode::Visual => usize::min(self.anchor, row)..usize::max(self.anchor, row) + 1,
+            _ => row..row + amount as usize,
+        };
+        // sorted views can show the todos in any order, the list is changed from the last todo to the first
+        let mut lines = rows
+            .map(|row| self.line_at(row))
+            .filter(|line| *line < len)
+            .collect::<Vec<_>>();
+        lines.sort_unstable();
+        lines
+    }
+
+    fn insert_key(&mut self, key: KeyEvent, list: &mut TodoList) {
+        let line = self.line();
+        let Some(todo) = list.data.get_mut(line) else {
+            self.keys.set_mode(Mode::Normal);
+            return;
+        };
+
+        let x = self.cursor.x as usize;
+        match key.code {
+            KeyCode::Char(ch) => {
+                todo.data.insert(byte_index(&todo.data, x), ch);
+                todo.touch();
+                self.cursor.x += 1;
+            }
+            KeyCode::Backspace if x > 0 => {
+                todo.data.remove(byte_index(&todo.data, x - 1));
+                todo.touch();
+                self.cursor.x -= 1;
+            }
+            KeyCode::Left => self.move_left(1),
+            KeyCode::Right if x < todo.data.chars().count() => self.cursor.x += 1,
+            KeyCode::Enter => {
+                self.keys.set_mode(Mode::Normal);
+                self.finish_insert(list);
+            }
+
+            _ => {}
+        }
+    }
+
+    fn finish_insert(&mut self, list: &mut TodoList) {
+        let line = self.line();
+        if let Some(todo) = list.data.get(line) {
+            if self.opened {
+                self.last_insert = todo.data.clone();
+            }
+            self.cursor.x = u16::min(self.cursor.x, todo.data.chars().count().saturating_sub(1) as u16);
+        }
+        self.opened = false;
+    }
+}
+
+fn byte_index(data: &str, chars: usize) -> usize {
+    data.char_indices().nth(chars).map(|(idx, _)| idx).unwrap_or(data.len())
 }
 
 impl CursorMovement for Editor {
     fn move_up(&mut self, amount: u16) {
-        if self.cursor.y == 0 {
-            self.scroll = self.scroll.saturating_sub(1);
-        }
-        self.cursor.y = self.cursor.y.saturating_sub(amount);
+        let row = self.row().saturating_sub(amount as usize) as u16;
+        let height = self.height();
+        self.cursor.scroll_to(row, &mut self.scroll, height);
         tracing::debug!("editor move_up: {:?}", self.cursor);
     }
 
     fn move_down(&mut self, amount: u16, max: u16) {
-        let min = u16::min(max, self.viewport.y());
-        if self.cursor.y + amount < min {
-            self.cursor.y += amount;
-        }
-        if self.cursor.y >= self.viewport.y().saturating_sub(2) && self.cursor.y + self.scroll <= max.saturating_sub(1)
-        {
-            self.scroll += 1;
-        }
+        let row = u16::min((self.row() as u16).saturating_add(amount), max.saturating_sub(1));
+        let height = self.height();
+        self.cursor.scroll_to(row, &mut self.scroll, height);
         tracing::info!("cursor {:?} scroll: {:?}", self.cursor, self.scroll);
     }
 
@@ -180,9 +637,36 @@ impl CursorMovement for Editor {
     fn move_right(&mut self, amount: u16, max: u16) {
         // padding for the todo status + the space at the end
         //let padding = 3_u16;
-        if self.cursor.x + amount < max {
-            self.cursor.x += amount;
-        }
+        self.cursor.x = u16::min(self.cursor.x.saturating_add(amount), max.saturating_sub(1));
         tracing::debug!("editor move_right: {:?}", self.cursor);
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+    use redo::TodoList;
+
+    use super::Editor;
+    use crate::event::EventHandler;
+
+    fn press(editor: &mut Editor, list: &mut TodoList, code: KeyCode) {
+        editor.handle_event(&Event::Key(KeyEvent::new(code, KeyModifiers::NONE)), list);
+    }
+
+    #[test]
+    fn test_cursor_in_characters() {
+        let mut list = TodoList::new("[work]".to_string(), "");
+        list.push_str("café crème");
+        let mut editor = Editor::default();
+        press(&mut editor, &mut list, KeyCode::Char('$'));
+        assert_eq!(editor.cursor.x, 9);
+        press(&mut editor, &mut list, KeyCode::Char('a'));
+        assert_eq!(editor.cursor.x, 10);
+        // leaving insert mode puts the cursor back on the last character, not past it
+        press(&mut editor, &mut list, KeyCode::Esc);
+        assert_eq!(editor.cursor.x, 9);
+        press(&mut editor, &mut list, KeyCode::Char('l'));
+        assert_eq!(editor.cursor.x, 9);
+    }
+}
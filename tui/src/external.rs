@@ -0,0 +1,101 @@
+use std::fs::OpenOptions;
+use std::hash::{BuildHasher, RandomState};
+use std::io::{ErrorKind, Write};
+use std::path::PathBuf;
+use std::process::Command;
+
+use redo::date::Date;
+use redo::todo::{Todo, TodoStatus};
+
+/// What an edit in `$EDITOR` replaces once the file is read back.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EditTarget {
+    /// the todo at the index of the list
+    Todo(usize, usize),
+    List(usize),
+}
+
+/// An edit in `$EDITOR`, written to a temporary file that stays around until it is read back without errors.
+#[derive(Debug, Clone)]
+pub struct ExternalEdit {
+    pub target: EditTarget,
+    pub path: PathBuf,
+    /// what the file started with, the edit only applies while the target still is this
+    pub original: String,
+}
+
+impl ExternalEdit {
+    /// writes `content` to a new file for the editor to open, its name can not be guessed so nothing else can
+    /// put a file or a link there first
+    pub fn new(target: EditTarget, content: &str) -> Result<Self, String> {
+        let mut options = OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+        let state = RandomState::new();
+        let mut attempt = 0_u8;
+        let (path, mut file) = loop {
+            let name = format!("redo-{}-{:016x}.todo", std::process::id(), state.hash_one(attempt));
+            let path = std::env::temp_dir().join(name);
+            match options.open(&path) {
+                Ok(file) => break (path, file),
+                Err(e) if e.kind() == ErrorKind::AlreadyExists && attempt < 16 => attempt += 1,
+                Err(e) => return Err(format!("could not create {}: {e}", path.display())),
+            }
+        };
+        file.write_all(content.as_bytes())
+            .map_err(|e| format!("could not write {}: {e}", path.display()))?;
+        Ok(Self {
+            target,
+            path,
+            original: content.to_string(),
+        })
+    }
+
+    /// runs the editor on the file and waits for it to exit, the terminal has to be given up before
+    pub fn run(&self) -> Result<(), String> {
+        let editor = editor();
+        let mut words = editor.split_whitespace();
+        let program = words.next().unwrap_or("vi");
+        let status = Command::new(program)
+            .args(words)
+            .arg(&self.path)
+            .status()
+            .map_err(|e| format!("could not run {editor}: {e}"))?;
+        match status.success() {
+            true => Ok(()),
+            false => Err(format!("{editor} exited with {status}")),
+        }
+    }
+
+    pub fn read(&self) -> Result<String, String> {
+        std::fs::read_to_string(&self.path).map_err(|e| format!("could not read {}: {e}", self.path.display()))
+    }
+
+    pub fn remove(&self) {
+        _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// `$VISUAL`, `$EDITOR` or vi when neither is set
+fn editor() -> String {
+    ["VISUAL", "EDITOR"]
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|editor| !editor.trim().is_empty())
+        .unwrap_or_else(|| "vi".to_string())
+}
+
+/// records today as the day the todos that are not in `before` were changed on
+pub fn stamp_changes(todos: &mut [Todo], before: &[Todo]) {
+    let today = Date::today();
+    for todo in todos.iter_mut().filter(|todo| !before.contains(todo)) {
+        todo.created.get_or_insert(today);
+        todo.modified = Some(today);
+        match todo.status {
+            TodoStatus::Complete => _ = todo.completed.get_or_insert(today),
+            TodoStatus::Incomplete => todo.completed = None,
+        }
+    }
+}
@@ -0,0 +1,316 @@
+use crossterm::event::{Event, KeyCode};
+use ratatui::layout::{Constraint, Flex, Layout, Position, Rect};
+use ratatui::style::Style;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Clear, List, ListItem, Paragraph};
+use ratatui::Frame;
+use redo::todo::{TodoListCollection, TodoStatus};
+
+use crate::event::EventHandler;
+use crate::theme::Theme;
+
+/// The ctrl-p picker, fuzzy matches every list title and todo of the collection.
+#[derive(Debug, Default)]
+pub struct Finder {
+    pub active: bool,
+    pub query: String,
+
+    candidates: Vec<Candidate>,
+    /// index into `candidates` of the highlighted one
+    selected: usize,
+    scroll: usize,
+}
+
+#[derive(Debug, Clone)]
+struct Candidate {
+    list: usize,
+    /// `None` for the list itself
+    todo: Option<usize>,
+    text: String,
+    score: i64,
+    /// the chars of `text` matched by the query
+    positions: Vec<usize>,
+}
+
+pub enum FinderState {
+    Close,
+    /// go to the list, and the todo in it if there is one
+    Jump(usize, Option<usize>),
+}
+
+impl EventHandler<&TodoListCollection, FinderState> for Finder {
+    fn handle_event(&mut self, event: &Event, collection: &TodoListCollection) -> Option<FinderState> {
+        let Event::Key(key) = event else {
+            return None;
+        };
+        match key.code {
+            KeyCode::Esc => return Some(FinderState::Close),
+            KeyCode::Enter => {
+                let candidate = self.candidates.get(self.selected)?;
+                return Some(FinderState::Jump(candidate.list, candidate.todo));
+            }
+            KeyCode::Up | KeyCode::BackTab => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Down | KeyCode::Tab => {
+                self.selected = usize::min(self.selected + 1, self.candidates.len().saturating_sub(1))
+            }
+            KeyCode::Char(ch) => {
+                self.query.push(ch);
+                self.rank(collection);
+            }
+            KeyCode::Backspace => {
+                self.query.pop();
+                self.rank(collection);
+            }
+            _ => {}
+        }
+        None
+    }
+}
+
+impl Finder {
+    pub fn open(&mut self, collection: &TodoListCollection) {
+        self.active = true;
+        self.query.clear();
+        self.rank(collection);
+    }
+
+    /// scores everything in the collection against the query, best matches first
+    fn rank(&mut self, collection: &TodoListCollection) {
+        let mut candidates = vec![];
+        for (list_idx, list) in collection.lists.iter().enumerate() {
+            candidates.push((list_idx, None, list.name()));
+            for (todo_idx, todo) in list.data.iter().enumerate() {
+                candidates.push((list_idx, Some(todo_idx), todo.data.as_str()));
+            }
+        }
+
+        self.candidates = candidates
+            .into_iter()
+            .filter_map(|(list, todo, text)| {
+                let (score, positions) = fuzzy_match(&self.query, text)?;
+                Some(Candidate {
+                    list,
+                    todo,
+                    text: text.to_string(),
+                    score,
+                    positions,
+                })
+            })
+            .collect();
+        // the sort is stable so equally good matches stay in the order of the file
+        self.candidates.sort_by_key(|candidate| -candidate.score);
+        self.selected = 0;
+        self.scroll = 0;
+    }
+
+    pub fn draw(&mut self, frame: &mut Frame, area: Rect, collection: &TodoListCollection, theme: &Theme) {
+        let [area] = Layout::vertical([Constraint::Percentage(70)])
+            .flex(Flex::Center)
+            .areas(area);
+        let [area] = Layout::horizontal([Constraint::Percentage(80)])
+            .flex(Flex::Center)
+            .areas(area);
+        let [results_area, preview_area] =
+            Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)]).areas(area);
+        frame.render_widget(Clear, area);
+
+        // the query line and the borders take up 4 lines
+        let height = results_area.height.saturating_sub(4) as usize;
+        if self.selected < self.scroll {
+            self.scroll = self.selected;
+        } else if height > 0 && self.selected >= self.scroll + height {
+            self.scroll = self.selected + 1 - height;
+        }
+
+        let mut items = vec![];
+        for (idx, candidate) in self.candidates.iter().enumerate().skip(self.scroll).take(height) {
+            let style = match idx == self.selected {
+                true => theme.selected,
+                false => theme.todo,
+            };
+            let mut line = highlight(&candidate.text, &candidate.positions, style, theme.matched);
+            let prefix = match candidate.todo {
+                Some(_) => format!("{} ", collection.lists[candidate.list].title),
+                None => "list ".to_string(),
+            };
+            line.spans.insert(0, Span::styled(prefix, theme.title));
+            items.push(ListItem::new(line));
+        }
+
+        let count = format!(" {}/{} ", self.candidates.len(), count(collection));
+        let block = Block::bordered()
+            .border_style(theme.popup)
+            .title_top(Line::from(" Find ").style(theme.title))
+            .title_bottom(Line::from(count).right_aligned());
+        let inner = block.inner(results_area);
+        frame.render_widget(block, results_area);
+        let [query_area, _, list_area] =
+            Layout::vertical([Constraint::Length(1), Constraint::Length(1), Constraint::Fill(1)]).areas(inner);
+        frame.render_widget(Paragraph::new(format!("> {}", self.query)), query_area);
+        frame.render_widget(List::new(items), list_area);
+        let x = query_area.x + 2 + self.query.chars().count() as u16;
+        frame.set_cursor_position(Position::new(
+            u16::min(x, query_area.right().saturating_sub(1)),
+            query_area.y,
+        ));
+
+        self.draw_preview(frame, preview_area, collection, theme);
+    }
+
+    /// the list of the highlighted match with the todo it is on
+    fn draw_preview(&self, frame: &mut Frame, area: Rect, collection: &TodoListCollection, theme: &Theme) {
+        let candidate = self.candidates.get(self.selected);
+        let list = candidate.and_then(|candidate| collection.lists.get(candidate.list));
+        let title = list.map(|list| list.title.as_str()).unwrap_or_default();
+        let block = Block::bordered()
+            .border_style(theme.popup)
+            .title_top(Line::from(format!(" {title} ")).style(theme.title));
+
+        let mut lines = vec![];
+        if let (Some(candidate), Some(list)) = (candidate, list) {
+            let height = block.inner(area).height as usize;
+            // keep the todo roughly in the middle
+            let start = candidate
+                .todo
+                .map(|todo| todo.saturating_sub(height / 2))
+                .unwrap_or_default();
+            for (idx, todo) in list.data.iter().enumerate().skip(start).take(height) {
+                let mut style = match todo.status {
+                    TodoStatus::Complete => theme.completed,
+                    TodoStatus::Incomplete => theme.todo,
+                };
+                if candidate.todo == Some(idx) {
+                    style = theme.selected;
+                }
+                lines.push(Line::styled(todo.to_string(), style));
+            }
+        }
+        frame.render_widget(Paragraph::new(lines).block(block), area);
+    }
+}
+
+fn count(collection: &TodoListCollection) -> usize {
+    collection.lists.iter().map(|list| list.len() + 1).sum()
+}
+
+/// `text` with the chars at `positions` styled as `matched`
+fn highlight<'a>(text: &str, positions: &[usize], style: Style, matched: Style) -> Line<'a> {
+    let spans = text
+        .chars()
+        .enumerate()
+        .map(|(idx, ch)| match positions.contains(&idx) {
+            true => Span::styled(ch.to_string(), matched),
+            false => Span::styled(ch.to_string(), style),
+        })
+        .collect::<Vec<_>>();
+    Line::from(spans)
+}
+
+/// scores how well `text` matches `pattern` when the chars of the pattern are found in order, but not
+/// necessarily next to each other, in the text. Case is ignored unless the pattern has uppercase letters in it.
+/// Matches in a row, at the start of words and in shorter texts score higher.
+fn fuzzy_match(pattern: &str, text: &str) -> Option<(i64, Vec<usize>)> {
+    let smartcase = pattern.chars().any(char::is_uppercase);
+    let fold = |ch: char| match smartcase {
+        true => ch,
+        false => ch.to_lowercase().next().unwrap_or(ch),
+    };
+    let pattern = pattern
+        .chars()
+        .map(fold)
+        .filter(|ch| !ch.is_whitespace())
+        .collect::<Vec<_>>();
+    let chars = text.chars().map(fold).collect::<Vec<_>>();
+    let Some(first) = pattern.first() else {
+        return Some((0, vec![]));
+    };
+
+    // the match is greedy after the first char, so every place the first char appears is tried
+    let mut best: Option<(i64, Vec<usize>)> = None;
+    for start in (0..chars.len()).filter(|idx| chars[*idx] == *first) {
+        let mut positions = vec![start];
+        let mut idx = start + 1;
+        for ch in &pattern[1..] {
+            let found = (idx..chars.len()).find(|idx| chars[*idx] == *ch)?;
+            positions.push(found);
+            idx = found + 1;
+        }
+
+        let score = score(&chars, &positions);
+        if best.as_ref().is_none_or(|(best, _)| score > *best) {
+            best = Some((score, positions));
+        }
+    }
+    best
+}
+
+fn score(chars: &[char], positions: &[usize]) -> i64 {
+    let mut score = 0;
+    for (nth, position) in positions.iter().enumerate() {
+        score += 16;
+        let word_start = *position == 0 || !chars[*position - 1].is_alphanumeric();
+        if word_start {
+            score += 12;
+        }
+        match nth.checked_sub(1).map(|prev| positions[prev]) {
+            Some(prev) if prev + 1 == *position => score += 20,
+            Some(prev) => score -= i64::min((*position - prev) as i64, 10),
+            None => score -= i64::min(*position as i64, 10),
+        }
+    }
+    score - chars.len() as i64 / 8
+}
+
+#[cfg(test)]
+mod test {
+    use redo::todo::TodoListCollection;
+    use redo::TodoList;
+
+    use super::{fuzzy_match, Finder};
+
+    #[test]
+    fn test_fuzzy_match() {
+        assert_eq!(fuzzy_match("", "groceries"), Some((0, vec![])));
+        assert_eq!(
+            fuzzy_match("gc", "groceries").map(|(_, positions)| positions),
+            Some(vec![0, 3])
+        );
+        assert_eq!(
+            fuzzy_match("g c", "groceries").map(|(_, positions)| positions),
+            Some(vec![0, 3])
+        );
+        assert_eq!(fuzzy_match("xyz", "groceries"), None);
+        assert_eq!(fuzzy_match("sg", "groceries"), None);
+        // case only matters once the pattern has uppercase letters
+        assert!(fuzzy_match("g", "Groceries").is_some());
+        assert!(fuzzy_match("G", "groceries").is_none());
+
+        let score = |text| fuzzy_match("dis", text).map(|(score, _)| score);
+        assert!(score("dishes") > score("do it soon"));
+        assert!(score("wash dishes") > score("candidates"));
+        assert!(score("dishes") > score("dishes and the laundry"));
+    }
+
+    #[test]
+    fn test_rank() {
+        let mut collection = TodoListCollection::default();
+        let mut list = TodoList::new("[groceries]".to_string(), "");
+        list.push_str("buy milk");
+        collection.push(list);
+        collection.push(TodoList::new("[garage]".to_string(), ""));
+
+        let mut finder = Finder {
+            query: "groc".to_string(),
+            ..Default::default()
+        };
+        finder.rank(&collection);
+        assert_eq!(finder.candidates.len(), 1);
+        // lists are matched by their name, without the brackets of the title
+        assert_eq!(finder.candidates[0].text, "groceries");
+        assert_eq!((finder.candidates[0].list, finder.candidates[0].todo), (0, None));
+
+        finder.query = "mlk".to_string();
+        finder.rank(&collection);
+        assert_eq!((finder.candidates[0].list, finder.candidates[0].todo), (0, Some(0)));
+    }
+}
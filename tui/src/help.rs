@@ -0,0 +1,130 @@
+use crossterm::event::{Event, KeyCode};
+use ratatui::layout::{Constraint, Flex, Layout, Rect};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Borders, Padding, Paragraph};
+use ratatui::Frame;
+
+use crate::event::EventHandler;
+use crate::keymap::{Action, Context, Keymap, Scope};
+use crate::modal::{Input, KeySequence};
+use crate::theme::Theme;
+
+/// keys that are not part of the keymap because they only ever edit text
+const TEXT_ENTRY: [(&str, &str); 6] = [
+    ("<Enter>", "Confirm popup / insert / search"),
+    ("<Esc>", "Cancel popup / insert / search"),
+    ("<BS>", "Delete character"),
+    ("<Left>/<Right>", "Move in insert mode"),
+    ("<Up>/<Down>", "Move in finder / history"),
+    ("<Tab>", "Complete command"),
+];
+
+#[derive(Debug, Default)]
+pub struct HelpScreen {
+    pub active: bool,
+    /// where help was opened from, it only shows the bindings that work there
+    pub context: Context,
+
+    keys: KeySequence,
+    scroll: u16,
+    /// the amount of lines that were visible when help was last drawn
+    height: u16,
+    lines: u16,
+}
+
+pub enum HelpState {
+    Close,
+}
+
+impl EventHandler<(), HelpState> for HelpScreen {
+    fn handle_event(&mut self, event: &Event, _: ()) -> Option<HelpState> {
+        let Event::Key(key) = event else {
+            return None;
+        };
+        if key.code == KeyCode::Esc {
+            return Some(HelpState::Close);
+        }
+
+        let Input::Action { action, count, .. } = self.keys.feed(key)? else {
+            return None;
+        };
+        let amount = count.unwrap_or(1);
+        let max = self.lines.saturating_sub(self.height);
+        match action {
+            Action::Up => self.scroll = self.scroll.saturating_sub(amount),
+            Action::Down => self.scroll = u16::min(self.scroll.saturating_add(amount), max),
+            Action::Top => self.scroll = 0,
+            Action::Bottom => self.scroll = max,
+            Action::Cancel => return Some(HelpState::Close),
+            _ => {}
+        }
+        None
+    }
+}
+
+impl HelpScreen {
+    pub fn open(&mut self, context: Context, keymap: &Keymap) {
+        self.active = true;
+        self.context = context;
+        self.scroll = 0;
+        self.keys = KeySequence::new(keymap.clone());
+    }
+
+    pub fn draw(&mut self, help_area: Rect, frame: &mut Frame, keymap: &Keymap, theme: &Theme) {
+        let mut help_vec: Vec<Line> = vec![];
+        let sections = [("Global", Scope::Global), (self.context.name(), self.context.scope())];
+        for (name, scope) in sections {
+            help_vec.push(Line::styled(format!(" {name} "), theme.help_header));
+            help_vec.push("".into());
+            for action in Action::ALL {
+                let keys = keymap.keys(scope, action);
+                let Some(help) = action.help(self.context) else {
+                    continue;
+                };
+                if keys.is_empty() {
+                    continue;
+                }
+                help_vec.push(format!("{:<16}{:<26}", keys.join("/"), help).into());
+            }
+            help_vec.push("".into());
+        }
+
+        help_vec.push(Line::styled(" Text Entry ", theme.help_header));
+        help_vec.push("".into());
+        for (keys, help) in TEXT_ENTRY {
+            help_vec.push(format!("{:<16}{:<26}", keys, help).into());
+        }
+
+        // borders and padding take up 6 lines
+        let [layout] = Layout::vertical([Constraint::Length(help_vec.len() as u16 + 6)])
+            .flex(Flex::Center)
+            .areas(help_area);
+        let [layout] = Layout::horizontal([Constraint::Length(56)])
+            .flex(Flex::Center)
+            .areas(layout);
+
+        self.lines = help_vec.len() as u16;
+        self.height = layout.height.saturating_sub(6);
+        self.scroll = u16::min(self.scroll, self.lines.saturating_sub(self.height));
+
+        let mut block = Block::default()
+            .borders(Borders::ALL)
+            .padding(Padding::new(5, 5, 2, 2))
+            .title_top(Line::from(format!(" {} ", self.context.name())).centered());
+        if self.lines > self.height {
+            let up = keymap.keys(Scope::Normal, Action::Up).join("/");
+            let down = keymap.keys(Scope::Normal, Action::Down).join("/");
+            let position = format!(" {}/{} ", self.scroll + self.height, self.lines);
+            block = block
+                .title_bottom(Line::from(format!(" {up} {down} to scroll ")).left_aligned())
+                .title_bottom(Line::from(position).right_aligned());
+        }
+
+        let help = Paragraph::new(help_vec)
+            .block(block)
+            .style(theme.help)
+            .scroll((self.scroll, 0))
+            .centered();
+        frame.render_widget(help, layout);
+    }
+}
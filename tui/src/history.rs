@@ -0,0 +1,138 @@
+use redo::todo::TodoListCollection;
+
+/// how many steps can be undone, the oldest ones are forgotten past it
+const LIMIT: usize = 100;
+
+/// Snapshots of the collection taken before every change, every snapshot is one undoable step.
+#[derive(Debug, Default)]
+pub struct History {
+    undo: Vec<TodoListCollection>,
+    redo: Vec<TodoListCollection>,
+    /// whether the last step was made while typing, consecutive typed changes are merged into one step
+    typing: bool,
+}
+
+impl History {
+    /// records `before` as an undoable step if the event that produced `after` changed anything
+    pub fn record(&mut self, before: TodoListCollection, after: &TodoListCollection, typing: bool) {
+        if before == *after {
+            return self.unchanged(typing);
+        }
+        if !(typing && self.typing) {
+            self.undo.push(before);
+            self.redo.clear();
+            if self.undo.len() > LIMIT {
+                self.undo.remove(0);
+            }
+        }
+        self.typing = typing;
+    }
+
+    /// for an event that did not change anything, it still ends a run of typed changes unless it was typed too
+    pub fn unchanged(&mut self, typing: bool) {
+        self.typing &= typing;
+    }
+
+    /// returns whether there was anything to undo
+    pub fn undo(&mut self, collection: &mut TodoListCollection, count: u16) -> bool {
+        let mut changed = false;
+        for _ in 0..count {
+            let Some(previous) = self.undo.pop() else {
+                break;
+            };
+            self.redo.push(std::mem::replace(collection, previous));
+            changed = true;
+        }
+        self.typing = false;
+        changed
+    }
+
+    /// returns whether there was anything to redo
+    pub fn redo(&mut self, collection: &mut TodoListCollection, count: u16) -> bool {
+        let mut changed = false;
+        for _ in 0..count {
+            let Some(next) = self.redo.pop() else {
+                break;
+            };
+            self.undo.push(std::mem::replace(collection, next));
+            changed = true;
+        }
+        self.typing = false;
+        changed
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use redo::todo::TodoListCollection;
+    use redo::TodoList;
+
+    use super::{History, LIMIT};
+
+    fn collection(names: &[&str]) -> TodoListCollection {
+        let mut collection = TodoListCollection::default();
+        for name in names {
+            collection.push(TodoList::new(format!("[{name}]"), ""));
+        }
+        collection
+    }
+
+    #[test]
+    fn test_undo_redo() {
+        let mut history = History::default();
+        let mut current = collection(&["a"]);
+        for names in [&["a", "b"][..], &["a", "b", "c"]] {
+            let before = std::mem::replace(&mut current, collection(names));
+            history.record(before, &current, false);
+        }
+        // nothing changed, so there is no step to undo
+        history.record(current.clone(), &current, false);
+
+        assert!(history.undo(&mut current, 1));
+        assert_eq!(current, collection(&["a", "b"]));
+        assert!(history.undo(&mut current, 5));
+        assert_eq!(current, collection(&["a"]));
+        assert!(!history.undo(&mut current, 1));
+
+        assert!(history.redo(&mut current, 1));
+        assert_eq!(current, collection(&["a", "b"]));
+        // a new change throws away what could be redone
+        let before = std::mem::replace(&mut current, collection(&["d"]));
+        history.record(before, &current, false);
+        assert!(!history.redo(&mut current, 1));
+        assert!(history.undo(&mut current, 1));
+        assert_eq!(current, collection(&["a", "b"]));
+    }
+
+    #[test]
+    fn test_typing() {
+        let mut history = History::default();
+        let mut current = collection(&[]);
+        for names in [&["a"][..], &["a", "b"]] {
+            let before = std::mem::replace(&mut current, collection(names));
+            history.record(before, &current, true);
+        }
+        // a key that is not typed ends the step, the next typed change is a step of its own
+        history.unchanged(false);
+        let before = std::mem::replace(&mut current, collection(&["a", "b", "c"]));
+        history.record(before, &current, true);
+
+        assert!(history.undo(&mut current, 1));
+        assert_eq!(current, collection(&["a", "b"]));
+        assert!(history.undo(&mut current, 1));
+        assert_eq!(current, collection(&[]));
+    }
+
+    #[test]
+    fn test_limit() {
+        let mut history = History::default();
+        let mut current = collection(&[]);
+        for idx in 0..LIMIT + 10 {
+            let name = idx.to_string();
+            let before = std::mem::replace(&mut current, collection(&[&name]));
+            history.record(before, &current, false);
+        }
+        assert!(history.undo(&mut current, u16::MAX));
+        assert_eq!(current, collection(&["9"]));
+    }
+}
@@ -0,0 +1,671 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+use crate::modal::Mode;
+
+/// Where a binding is active, global bindings are checked before the focused widget sees the key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scope {
+    Global,
+    Normal,
+    Visual,
+}
+
+impl Scope {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Scope::Global => "global",
+            Scope::Normal => "normal",
+            Scope::Visual => "visual",
+        }
+    }
+
+    pub fn of(mode: Mode) -> Option<Self> {
+        match mode {
+            Mode::Normal => Some(Scope::Normal),
+            Mode::Visual => Some(Scope::Visual),
+            Mode::Insert => None,
+        }
+    }
+}
+
+/// The part of the app keys are typed in, the same action can mean something else in each of them.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Context {
+    #[default]
+    Selection,
+    Editor,
+    Visual,
+}
+
+impl Context {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Context::Selection => "Selection Mode",
+            Context::Editor => "Editor Mode",
+            Context::Visual => "Visual Mode",
+        }
+    }
+
+    pub fn scope(&self) -> Scope {
+        match self {
+            Context::Selection | Context::Editor => Scope::Normal,
+            Context::Visual => Scope::Visual,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Quit,
+    Help,
+    Finder,
+    Up,
+    Down,
+    Left,
+    Right,
+    Top,
+    Bottom,
+    LineStart,
+    LineEnd,
+    Insert,
+    Append,
+    OpenBelow,
+    OpenAbove,
+    Visual,
+    DeleteLine,
+    Yank,
+    Paste,
+    PasteAbove,
+    Toggle,
+    Remove,
+    Move,
+    Tag,
+    Notes,
+    External,
+    Undo,
+    Redo,
+    Search,
+    NextMatch,
+    PrevMatch,
+    Command,
+    NextTab,
+    PrevTab,
+    Confirm,
+    Cancel,
+    Repeat,
+}
+
+impl Action {
+    pub const ALL: [Action; 37] = [
+        Action::Quit,
+        Action::Help,
+        Action::Finder,
+        Action::Up,
+        Action::Down,
+        Action::Left,
+        Action::Right,
+        Action::Top,
+        Action::Bottom,
+        Action::LineStart,
+        Action::LineEnd,
+        Action::Insert,
+        Action::Append,
+        Action::OpenBelow,
+        Action::OpenAbove,
+        Action::Visual,
+        Action::DeleteLine,
+        Action::Yank,
+        Action::Paste,
+        Action::PasteAbove,
+        Action::Toggle,
+        Action::Remove,
+        Action::Move,
+        Action::Tag,
+        Action::Notes,
+        Action::External,
+        Action::Undo,
+        Action::Redo,
+        Action::Search,
+        Action::NextMatch,
+        Action::PrevMatch,
+        Action::Command,
+        Action::NextTab,
+        Action::PrevTab,
+        Action::Confirm,
+        Action::Cancel,
+        Action::Repeat,
+    ];
+
+    /// the name used for the action in the config file
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::Quit => "quit",
+            Action::Help => "help",
+            Action::Finder => "finder",
+            Action::Up => "up",
+            Action::Down => "down",
+            Action::Left => "left",
+            Action::Right => "right",
+            Action::Top => "top",
+            Action::Bottom => "bottom",
+            Action::LineStart => "line_start",
+            Action::LineEnd => "line_end",
+            Action::Insert => "insert",
+            Action::Append => "append",
+            Action::OpenBelow => "open_below",
+            Action::OpenAbove => "open_above",
+            Action::Visual => "visual",
+            Action::DeleteLine => "delete_line",
+            Action::Yank => "yank",
+            Action::Paste => "paste",
+            Action::PasteAbove => "paste_above",
+            Action::Toggle => "toggle",
+            Action::Remove => "remove",
+            Action::Move => "move",
+            Action::Tag => "tag",
+            Action::Notes => "notes",
+            Action::External => "external_edit",
+            Action::Undo => "undo",
+            Action::Redo => "redo",
+            Action::Search => "search",
+            Action::NextMatch => "next_match",
+            Action::PrevMatch => "prev_match",
+            Action::Command => "command",
+            Action::NextTab => "next_tab",
+            Action::PrevTab => "prev_tab",
+            Action::Confirm => "confirm",
+            Action::Cancel => "cancel",
+            Action::Repeat => "repeat",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Action::Quit => "Save and quit",
+            Action::Help => "Toggle help",
+            Action::Finder => "Find list or todo",
+            Action::Up => "Move up",
+            Action::Down => "Move down",
+            Action::Left => "Move left",
+            Action::Right => "Move right",
+            Action::Top => "Go to first line",
+            Action::Bottom => "Go to last line",
+            Action::LineStart => "Go to start of line",
+            Action::LineEnd => "Go to end of line",
+            Action::Insert => "Insert text",
+            Action::Append => "Append text",
+            Action::OpenBelow => "Open todo below",
+            Action::OpenAbove => "Open todo above",
+            Action::Visual => "Toggle visual mode",
+            Action::DeleteLine => "Delete lines",
+            Action::Yank => "Yank lines",
+            Action::Paste => "Paste below",
+            Action::PasteAbove => "Paste above",
+            Action::Toggle => "Toggle done",
+            Action::Remove => "Remove",
+            Action::Move => "Move to list",
+            Action::Tag => "Tag todos",
+            Action::Notes => "Show notes",
+            Action::External => "Edit todo in $EDITOR",
+            Action::Undo => "Undo",
+            Action::Redo => "Redo",
+            Action::Search => "Search",
+            Action::NextMatch => "Next match",
+            Action::PrevMatch => "Previous match",
+            Action::Command => "Command line",
+            Action::NextTab => "Next tab",
+            Action::PrevTab => "Previous tab",
+            Action::Confirm => "Create todo",
+            Action::Cancel => "Back to lists",
+            Action::Repeat => "Repeat last change",
+        }
+    }
+
+    /// what the action does in `context`, `None` if it does nothing there
+    pub fn help(&self, context: Context) -> Option<&'static str> {
+        use Action::*;
+
+        let help = match (context, self) {
+            (_, Quit | Help | Finder) => self.description(),
+            (
+                Context::Selection,
+                Up | Down | Top | Bottom | Undo | Redo | Search | NextMatch | PrevMatch | Command | NextTab | PrevTab,
+            ) => self.description(),
+            (Context::Selection, Toggle) => "Open list",
+            (Context::Selection, Remove | DeleteLine) => "Remove list",
+            (Context::Selection, Confirm | OpenBelow | OpenAbove) => "Create list",
+            (Context::Selection, Cancel) => "Clear search",
+            (Context::Selection, External) => "Edit list in $EDITOR",
+            (Context::Selection, _) => return None,
+            (Context::Visual, Visual | Cancel) => "Leave visual mode",
+            (Context::Visual, DeleteLine) => "Delete selection",
+            (Context::Visual, Yank) => "Yank selection",
+            (_, _) => self.description(),
+        };
+        Some(help)
+    }
+
+    /// actions that change the todos, they are turned off for files opened read-only
+    pub fn edits(&self) -> bool {
+        self.is_change()
+            || matches!(
+                self,
+                Action::Insert
+                    | Action::Append
+                    | Action::Move
+                    | Action::Tag
+                    | Action::External
+                    | Action::Undo
+                    | Action::Redo
+                    | Action::Repeat
+                    | Action::Confirm
+            )
+    }
+
+    /// commands that change the list, these are what `.` repeats
+    pub fn is_change(&self) -> bool {
+        matches!(
+            self,
+            Action::DeleteLine
+                | Action::Paste
+                | Action::PasteAbove
+                | Action::OpenBelow
+                | Action::OpenAbove
+                | Action::Toggle
+                | Action::Remove
+        )
+    }
+}
+
+impl std::str::FromStr for Action {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Action::ALL
+            .into_iter()
+            .find(|action| action.name() == name)
+            .ok_or_else(|| format!("unknown action `{name}`"))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Binding {
+    pub scope: Scope,
+    pub keys: Vec<KeyEvent>,
+    pub action: Action,
+}
+
+/// Every key binding of the app, the defaults can be overridden per action from the config file.
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    bindings: Vec<Binding>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        use Action::*;
+
+        let global = [(Quit, "<C-q>"), (Help, "?"), (Finder, "<C-p>")];
+
+        let motions = [
+            (Up, "k"),
+            (Up, "<Up>"),
+            (Down, "j"),
+            (Down, "<Down>"),
+            (Left, "h"),
+            (Left, "<Left>"),
+            (Right, "l"),
+            (Right, "<Right>"),
+            (Top, "gg"),
+            (Bottom, "G"),
+            (LineStart, "0"),
+            (LineEnd, "$"),
+        ];
+
+        let normal = [
+            (Insert, "i"),
+            (Append, "a"),
+            (OpenBelow, "o"),
+            (OpenAbove, "O"),
+            (Visual, "v"),
+            (Visual, "V"),
+            (DeleteLine, "dd"),
+            (Yank, "yy"),
+            (Paste, "p"),
+            (PasteAbove, "P"),
+            (Toggle, "<Space>"),
+            (Remove, "x"),
+            (Move, "m"),
+            (Tag, "t"),
+            (Notes, "K"),
+            (External, "E"),
+            (Undo, "u"),
+            (Redo, "<C-r>"),
+            (Search, "/"),
+            (NextMatch, "n"),
+            (PrevMatch, "N"),
+            (Command, ":"),
+            (NextTab, "gt"),
+            (PrevTab, "gT"),
+            (Repeat, "."),
+            (Confirm, "<Enter>"),
+            (Cancel, "<Esc>"),
+        ];
+
+        let visual = [
+            (Visual, "v"),
+            (Visual, "V"),
+            (DeleteLine, "d"),
+            (DeleteLine, "x"),
+            (Yank, "y"),
+            (Toggle, "<Space>"),
+            (Move, "m"),
+            (Tag, "t"),
+            (Cancel, "<Esc>"),
+        ];
+
+        let mut keymap = Self { bindings: vec![] };
+        let mut push = |scope, action, keys: &str| {
+            let keys = parse_keys(keys).expect("default bindings are valid");
+            keymap.bindings.push(Binding { scope, keys, action });
+        };
+        for (action, keys) in global {
+            push(Scope::Global, action, keys);
+        }
+        for (action, keys) in motions {
+            push(Scope::Normal, action, keys);
+            push(Scope::Visual, action, keys);
+        }
+        for (action, keys) in normal {
+            push(Scope::Normal, action, keys);
+        }
+        for (action, keys) in visual {
+            push(Scope::Visual, action, keys);
+        }
+        keymap
+    }
+}
+
+impl Keymap {
+    pub fn bindings(&self, scope: Scope) -> impl Iterator<Item = &Binding> {
+        self.bindings.iter().filter(move |binding| binding.scope == scope)
+    }
+
+    /// replaces the keys bound to `action` in `scope`, an empty list unbinds the action
+    pub fn bind(&mut self, scope: Scope, action: Action, keys: Vec<Vec<KeyEvent>>) {
+        self.bindings
+            .retain(|binding| binding.scope != scope || binding.action != action);
+        for keys in keys {
+            self.bindings.push(Binding { scope, keys, action });
+        }
+    }
+
+    /// the global action bound to a single key
+    pub fn global(&self, key: &KeyEvent) -> Option<Action> {
+        let key = normalize(key);
+        self.bindings(Scope::Global)
+            .find(|binding| binding.keys == [key])
+            .map(|binding| binding.action)
+    }
+
+    /// the keys bound to `action` in `scope`, formatted like in the config file
+    pub fn keys(&self, scope: Scope, action: Action) -> Vec<String> {
+        self.bindings(scope)
+            .filter(|binding| binding.action == action)
+            .map(|binding| format_keys(&binding.keys))
+            .collect()
+    }
+
+    /// bindings that can never be reached because another binding is typed first
+    pub fn conflicts(&self) -> Vec<String> {
+        let mut conflicts = vec![];
+        for (idx, binding) in self.bindings.iter().enumerate() {
+            if binding.scope == Scope::Global && binding.keys.len() != 1 {
+                conflicts.push(format!(
+                    "global binding `{}` for {} must be a single key",
+                    format_keys(&binding.keys),
+                    binding.action.name()
+                ));
+            }
+            if binding.scope != Scope::Global {
+                if let Some(KeyCode::Char('1'..='9')) = binding.keys.first().map(|key| key.code) {
+                    conflicts.push(format!(
+                        "`{}` for {} starts with a count",
+                        format_keys(&binding.keys),
+                        binding.action.name()
+                    ));
+                }
+            }
+
+            for other in &self.bindings[idx + 1..] {
+                let shadows = match (binding.scope, other.scope) {
+                    (a, b) if a == b => true,
+                    (Scope::Global, _) | (_, Scope::Global) => true,
+                    _ => false,
+                };
+                let same = binding.keys == other.keys && binding.action == other.action;
+                let prefix = binding.keys.starts_with(&other.keys) || other.keys.starts_with(&binding.keys);
+                if shadows && prefix && !same {
+                    conflicts.push(format!(
+                        "`{}` ({} {}) conflicts with `{}` ({} {})",
+                        format_keys(&binding.keys),
+                        binding.scope.name(),
+                        binding.action.name(),
+                        format_keys(&other.keys),
+                        other.scope.name(),
+                        other.action.name()
+                    ));
+                }
+            }
+        }
+        conflicts
+    }
+}
+
+/// strips the shift modifier of characters, it is already part of the character
+pub fn normalize(key: &KeyEvent) -> KeyEvent {
+    let mut modifiers = key.modifiers;
+    if let KeyCode::Char(..) = key.code {
+        modifiers.remove(KeyModifiers::SHIFT);
+    }
+    KeyEvent::new(key.code, modifiers)
+}
+
+/// parses vim style key notation like `gg`, `<C-q>` or `<Space>`
+pub fn parse_keys(notation: &str) -> Result<Vec<KeyEvent>, String> {
+    let mut keys = vec![];
+    let mut chars = notation.chars();
+    while let Some(ch) = chars.next() {
+        if ch != '<' {
+            keys.push(KeyEvent::new(KeyCode::Char(ch), KeyModifiers::NONE));
+            continue;
+        }
+
+        let rest = chars.as_str();
+        let Some(end) = rest.find('>') else {
+            // a lone `<` is the key itself
+            keys.push(KeyEvent::new(KeyCode::Char('<'), KeyModifiers::NONE));
+            continue;
+        };
+        keys.push(parse_key(&rest[..end]).ok_or_else(|| format!("unknown key `<{}>` in `{notation}`", &rest[..end]))?);
+        chars = rest[end + 1..].chars();
+    }
+
+    if keys.is_empty() {
+        return Err("empty key binding".to_string());
+    }
+    Ok(keys)
+}
+
+fn parse_key(name: &str) -> Option<KeyEvent> {
+    let mut modifiers = KeyModifiers::NONE;
+    let mut name = name;
+    while name.len() > 2 && name.as_bytes()[1] == b'-' {
+        match name.as_bytes()[0].to_ascii_uppercase() {
+            b'C' => modifiers |= KeyModifiers::CONTROL,
+            b'A' | b'M' => modifiers |= KeyModifiers::ALT,
+            b'S' => modifiers |= KeyModifiers::SHIFT,
+            _ => return None,
+        }
+        name = &name[2..];
+    }
+
+    let code = match name.to_ascii_lowercase().as_str() {
+        "space" => KeyCode::Char(' '),
+        "enter" | "cr" | "return" => KeyCode::Enter,
+        "esc" => KeyCode::Esc,
+        "tab" => KeyCode::Tab,
+        "bs" | "backspace" => KeyCode::Backspace,
+        "del" | "delete" => KeyCode::Delete,
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        "pageup" => KeyCode::PageUp,
+        "pagedown" => KeyCode::PageDown,
+        "lt" => KeyCode::Char('<'),
+        lower => match lower.strip_prefix('f').and_then(|num| num.parse::<u8>().ok()) {
+            Some(num) if name.len() > 1 => KeyCode::F(num),
+            _ => {
+                let mut chars = name.chars();
+                let ch = chars.next()?;
+                if chars.next().is_some() {
+                    return None;
+                }
+                KeyCode::Char(ch)
+            }
+        },
+    };
+    Some(normalize(&KeyEvent::new(code, modifiers)))
+}
+
+pub fn format_keys(keys: &[KeyEvent]) -> String {
+    keys.iter().map(format_key).collect()
+}
+
+fn format_key(key: &KeyEvent) -> String {
+    let name = match key.code {
+        KeyCode::Char(' ') => "Space".to_string(),
+        KeyCode::Char('<') => "lt".to_string(),
+        KeyCode::Char(ch) if key.modifiers.is_empty() => return ch.to_string(),
+        KeyCode::Char(ch) => ch.to_string(),
+        KeyCode::Enter => "Enter".to_string(),
+        KeyCode::Esc => "Esc".to_string(),
+        KeyCode::Tab => "Tab".to_string(),
+        KeyCode::Backspace => "BS".to_string(),
+        KeyCode::Delete => "Del".to_string(),
+        KeyCode::Up => "Up".to_string(),
+        KeyCode::Down => "Down".to_string(),
+        KeyCode::Left => "Left".to_string(),
+        KeyCode::Right => "Right".to_string(),
+        KeyCode::Home => "Home".to_string(),
+        KeyCode::End => "End".to_string(),
+        KeyCode::PageUp => "PageUp".to_string(),
+        KeyCode::PageDown => "PageDown".to_string(),
+        KeyCode::F(num) => format!("F{num}"),
+        code => format!("{code:?}"),
+    };
+
+    let mut modifiers = String::default();
+    if key.modifiers.contains(KeyModifiers::CONTROL) {
+        modifiers.push_str("C-");
+    }
+    if key.modifiers.contains(KeyModifiers::ALT) {
+        modifiers.push_str("A-");
+    }
+    if key.modifiers.contains(KeyModifiers::SHIFT) {
+        modifiers.push_str("S-");
+    }
+    format!("<{modifiers}{name}>")
+}
+
+#[cfg(test)]
+mod test {
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+    use super::{format_keys, parse_keys, Action, Keymap, Scope};
+
+    fn key(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    #[test]
+    fn test_parse_keys() {
+        let none = KeyModifiers::NONE;
+        assert_eq!(
+            parse_keys("gg"),
+            Ok(vec![key(KeyCode::Char('g'), none), key(KeyCode::Char('g'), none)])
+        );
+        assert_eq!(
+            parse_keys("<C-q>"),
+            Ok(vec![key(KeyCode::Char('q'), KeyModifiers::CONTROL)])
+        );
+        assert_eq!(parse_keys("<space>"), Ok(vec![key(KeyCode::Char(' '), none)]));
+        assert_eq!(
+            parse_keys("<A-S-F5>"),
+            Ok(vec![key(KeyCode::F(5), KeyModifiers::ALT | KeyModifiers::SHIFT)])
+        );
+        assert_eq!(parse_keys("<lt>"), parse_keys("<"));
+        assert_eq!(
+            parse_keys("d<Esc>"),
+            Ok(vec![key(KeyCode::Char('d'), none), key(KeyCode::Esc, none)])
+        );
+        // shift is part of the character already
+        assert_eq!(parse_keys("<S-G>"), parse_keys("G"));
+
+        for keys in ["gg", "<C-q>", "<Space>", "<lt>", "<A-F5>", "d<Esc>"] {
+            let parsed = parse_keys(keys).expect("valid keys");
+            assert_eq!(parse_keys(&format_keys(&parsed)), Ok(parsed));
+        }
+    }
+
+    #[test]
+    fn test_parse_bad_keys() {
+        assert_eq!(parse_keys(""), Err("empty key binding".to_string()));
+        assert_eq!(
+            parse_keys("<Nope>"),
+            Err("unknown key `<Nope>` in `<Nope>`".to_string())
+        );
+        assert!(parse_keys("<X-a>").is_err());
+        assert!(parse_keys("g<C-ab>").is_err());
+        assert!("jump".parse::<Action>().is_err());
+    }
+
+    #[test]
+    fn test_conflicts() {
+        assert!(Keymap::default().conflicts().is_empty());
+
+        let mut keymap = Keymap::default();
+        keymap.bind(Scope::Normal, Action::Down, vec![parse_keys("g").expect("valid keys")]);
+        assert_eq!(
+            keymap.conflicts(),
+            vec![
+                "`gg` (normal top) conflicts with `g` (normal down)",
+                "`gt` (normal next_tab) conflicts with `g` (normal down)",
+                "`gT` (normal prev_tab) conflicts with `g` (normal down)",
+            ]
+        );
+
+        let mut keymap = Keymap::default();
+        keymap.bind(Scope::Global, Action::Quit, vec![parse_keys("ZZ").expect("valid keys")]);
+        keymap.bind(Scope::Normal, Action::Yank, vec![parse_keys("2y").expect("valid keys")]);
+        assert_eq!(
+            keymap.conflicts(),
+            vec![
+                "global binding `ZZ` for quit must be a single key",
+                "`2y` for yank starts with a count",
+            ]
+        );
+
+        // a global key takes the key from every scope
+        let mut keymap = Keymap::default();
+        keymap.bind(
+            Scope::Global,
+            Action::Finder,
+            vec![parse_keys("u").expect("valid keys")],
+        );
+        assert_eq!(keymap.conflicts().len(), 1);
+    }
+}
@@ -1,34 +1,158 @@
 use std::fs::OpenOptions;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
 
 use app::App;
+use args::{Args, Parsed, USAGE};
+use config::Config;
+use redo::query::Query;
+use theme::Theme;
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_subscriber::filter::LevelFilter;
 use tracing_subscriber::FmtSubscriber;
 
 mod app;
+mod args;
+mod command;
+mod config;
 mod cursor;
 mod editor;
 mod event;
+mod external;
+mod finder;
+mod help;
+mod history;
+mod keymap;
+mod modal;
+mod notes;
+mod paths;
+mod recovery;
+mod search;
 mod selection;
+mod status;
+mod tabs;
+mod theme;
 mod tui;
 mod viewport;
 
 fn main() {
-    _ = OpenOptions::new()
+    let mut args = match Args::parse(std::env::args().skip(1)) {
+        Ok(Parsed::Run(args)) => args,
+        Ok(Parsed::Help) => {
+            print!("{USAGE}");
+            return;
+        }
+        Ok(Parsed::Version) => {
+            println!("redo {}", env!("CARGO_PKG_VERSION"));
+            return;
+        }
+        Err(e) => {
+            eprintln!("{e}\n\n{USAGE}");
+            std::process::exit(1);
+        }
+    };
+
+    let _guard = init_logging(args.log_file.clone().or_else(paths::log_file));
+
+    let config = match args.config.clone().or_else(paths::config_file) {
+        Some(path) => Config::load(&path),
+        None => Ok(Config::default()),
+    };
+    let mut config = match config {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
+    };
+
+    if Theme::no_color() {
+        config.theme = config.theme.without_colors();
+    }
+
+    if args.files.is_empty() {
+        let Some(file) = paths::default_file() else {
+            eprintln!("no file given and neither $XDG_DATA_HOME nor $HOME is set\n\n{USAGE}");
+            std::process::exit(1);
+        };
+        if let Some(dir) = file.parent() {
+            _ = std::fs::create_dir_all(dir);
+        }
+        args.files.push(file.to_string_lossy().into_owned());
+    }
+
+    if let Some(query) = &args.query {
+        if let Err(e) = print_query(&args.files[0], query) {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    let mut app = match App::init(args, config) {
+        Ok(app) => app,
+        Err(e) => {
+            eprintln!("{e}");
+            std::process::exit(1);
+        }
+    };
+    let code = app.run();
+    if code != 0 {
+        // exiting skips destructors, the log is flushed first
+        drop(_guard);
+        std::process::exit(code);
+    }
+}
+
+/// truncates the log and logs to it at the level in `RUST_LOG`, the log is left out when it can not be written
+fn init_logging(file: Option<PathBuf>) -> Option<WorkerGuard> {
+    let file = file?;
+    let dir = file
+        .parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    _ = std::fs::create_dir_all(dir);
+    OpenOptions::new()
         .write(true)
         .create(true)
         .truncate(true)
-        .open("log")
-        .expect("truncating log file failed");
+        .open(&file)
+        .ok()?;
 
-    let appender = tracing_appender::rolling::never(".", "log");
-    let (appender, _guard) = tracing_appender::non_blocking(appender);
+    let appender = tracing_appender::rolling::never(dir, file.file_name()?);
+    let (appender, guard) = tracing_appender::non_blocking(appender);
     let subscriber = FmtSubscriber::builder()
-        .with_max_level(tracing::Level::TRACE)
+        .with_max_level(log_level(std::env::var("RUST_LOG").ok().as_deref()))
         .with_writer(appender)
         .with_ansi(false)
         .finish();
 
     let _ = tracing::subscriber::set_global_default(subscriber);
+    Some(guard)
+}
+
+/// the most verbose level in `RUST_LOG`, either a level on its own or `target=level` directives, `info` when it
+/// is not set
+fn log_level(var: Option<&str>) -> LevelFilter {
+    let Some(var) = var else {
+        return LevelFilter::INFO;
+    };
+    var.split(',')
+        .filter_map(|directive| {
+            let level = directive.rsplit('=').next().unwrap_or(directive);
+            LevelFilter::from_str(level.trim()).ok()
+        })
+        .max()
+        .unwrap_or(LevelFilter::INFO)
+}
 
-    let args = std::env::args();
-    App::init(args).run();
+/// prints the todos of `file` matching `query` without starting the tui
+fn print_query(file: &str, query: &str) -> Result<(), String> {
+    let query = query.parse::<Query>()?;
+    let content = std::fs::read_to_string(file).map_err(|e| format!("could not read {file}: {e}"))?;
+    let collection = tabs::parse(file, &content)?;
+    for found in collection.query(&query) {
+        println!("{} {}", found.list.title, found.todo);
+    }
+    Ok(())
 }
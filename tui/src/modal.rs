@@ -0,0 +1,305 @@
+use crossterm::event::{KeyCode, KeyEvent};
+
+use crate::keymap::{normalize, Action, Keymap, Scope};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    #[default]
+    Normal,
+    Insert,
+    Visual,
+}
+
+impl std::fmt::Display for Mode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mode = match self {
+            Mode::Normal => "NORMAL",
+            Mode::Insert => "INSERT",
+            Mode::Visual => "VISUAL",
+        };
+        write!(f, "{}", mode)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Input {
+    Action {
+        action: Action,
+        count: Option<u16>,
+        repeated: bool,
+    },
+    /// a key pressed while in insert mode, it is up to the caller to decide what to do with it
+    Text(KeyEvent),
+}
+
+/// The key-sequence state machine shared by the editor and the selection bar, it collects counts and
+/// multi key sequences like `dd` or `gg` and turns them into an [`Action`] using the bindings of the [`Keymap`].
+#[derive(Debug, Clone)]
+pub struct KeySequence {
+    mode: Mode,
+    count: Option<u16>,
+    pending: Vec<KeyEvent>,
+    last_change: Option<(Action, Option<u16>)>,
+    keymap: Keymap,
+}
+
+impl Default for KeySequence {
+    fn default() -> Self {
+        Self::new(Keymap::default())
+    }
+}
+
+impl KeySequence {
+    pub fn new(keymap: Keymap) -> Self {
+        Self {
+            mode: Mode::default(),
+            count: None,
+            pending: vec![],
+            last_change: None,
+            keymap,
+        }
+    }
+
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    pub fn set_mode(&mut self, mode: Mode) {
+        self.mode = mode;
+        self.reset();
+    }
+
+    /// the count and keys typed so far that did not resolve to a command yet
+    pub fn pending(&self) -> String {
+        let mut tmp = self.count.map(|count| count.to_string()).unwrap_or_default();
+        for key in &self.pending {
+            if let KeyCode::Char(ch) = key.code {
+                tmp.push(ch);
+            }
+        }
+        tmp
+    }
+
+    /// whether feeding `key` could change the todos, so the collection only has to be kept for undo when it can
+    pub fn may_change(&self, key: &KeyEvent) -> bool {
+        let key = normalize(key);
+        let Some(scope) = Scope::of(self.mode) else {
+            // everything typed in insert mode is text, leaving it can drop an empty todo
+            return true;
+        };
+        let resolve = |keys: &[KeyEvent]| {
+            self.keymap
+                .bindings(scope)
+                .find(|binding| binding.keys == keys)
+                .map(|binding| binding.action)
+        };
+        let mut keys = self.pending.clone();
+        keys.push(key);
+        // a key breaking the pending sequence is tried on its own, like `feed` does
+        match resolve(&keys).or_else(|| resolve(&[key])) {
+            Some(Action::Repeat) => self.last_change.is_some(),
+            Some(action) => action.is_change(),
+            None => false,
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.count = None;
+        self.pending.clear();
+    }
+
+    pub fn feed(&mut self, key: &KeyEvent) -> Option<Input> {
+        let key = normalize(key);
+
+        if self.mode == Mode::Insert {
+            if key.code == KeyCode::Esc {
+                self.set_mode(Mode::Normal);
+                return Some(Input::Action {
+                    action: Action::Cancel,
+                    count: None,
+                    repeated: false,
+                });
+            }
+            return Some(Input::Text(key));
+        }
+
+        if self.pending.is_empty() {
+            if let KeyCode::Char(ch @ '0'..='9') = key.code {
+                // a leading 0 is not a count
+                if ch != '0' || self.count.is_some() {
+                    let digit = ch.to_digit(10).unwrap_or_default() as u16;
+                    let count = self.count.unwrap_or_default();
+                    self.count = Some(count.saturating_mul(10).saturating_add(digit));
+                    return None;
+                }
+            }
+        }
+
+        let scope = Scope::of(self.mode)?;
+        self.pending.push(key);
+
+        let (prefix, action) = {
+            let mut candidates = self
+                .keymap
+                .bindings(scope)
+                .filter(|binding| binding.keys.starts_with(&self.pending))
+                .peekable();
+            let prefix = candidates.peek().is_some();
+            // a complete sequence wins over longer ones sharing its prefix, the keymap reports those as conflicts
+            let action = candidates
+                .find(|binding| binding.keys.len() == self.pending.len())
+                .map(|binding| binding.action);
+            (prefix, action)
+        };
+        if !prefix {
+            // a key that breaks a sequence like `g` then `j` is not thrown away but tried on its own
+            let retry = self.pending.len() > 1;
+            self.reset();
+            if retry {
+                return self.feed(&key);
+            }
+        }
+        let action = action?;
+
+        let count = self.count.take();
+        self.pending.clear();
+
+        match action {
+            Action::Repeat => {
+                let (action, last_count) = self.last_change?;
+                return Some(Input::Action {
+                    action,
+                    count: count.or(last_count),
+                    repeated: true,
+                });
+            }
+            Action::Visual => {
+                self.mode = match self.mode {
+                    Mode::Visual => Mode::Normal,
+                    _ => Mode::Visual,
+                };
+            }
+            Action::Cancel => self.mode = Mode::Normal,
+            _ => {}
+        }
+
+        if action.is_change() {
+            self.last_change = Some((action, count));
+            // changes in visual mode act on the selection and leave visual mode
+            self.mode = Mode::Normal;
+        }
+        if matches!(action, Action::Yank | Action::Move | Action::Tag) {
+            self.mode = Mode::Normal;
+        }
+
+        Some(Input::Action {
+            action,
+            count,
+            repeated: false,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+    use super::{Input, KeySequence, Mode};
+    use crate::keymap::Action;
+
+    /// feeds every key of `keys` and returns what the last one resolved to
+    fn feed(sequence: &mut KeySequence, keys: &str) -> Option<Input> {
+        keys.chars()
+            .map(|ch| sequence.feed(&KeyEvent::new(KeyCode::Char(ch), KeyModifiers::NONE)))
+            .last()
+            .flatten()
+    }
+
+    fn action(action: Action, count: Option<u16>, repeated: bool) -> Option<Input> {
+        Some(Input::Action {
+            action,
+            count,
+            repeated,
+        })
+    }
+
+    #[test]
+    fn test_counts() {
+        let mut keys = KeySequence::default();
+        assert_eq!(feed(&mut keys, "j"), action(Action::Down, None, false));
+        assert_eq!(feed(&mut keys, "3"), None);
+        assert_eq!(keys.pending(), "3");
+        assert_eq!(feed(&mut keys, "j"), action(Action::Down, Some(3), false));
+        assert_eq!(feed(&mut keys, "12gg"), action(Action::Top, Some(12), false));
+        assert_eq!(keys.pending(), "");
+        // counts too large for the cursor stop growing instead of overflowing
+        assert_eq!(feed(&mut keys, "999999j"), action(Action::Down, Some(u16::MAX), false));
+    }
+
+    #[test]
+    fn test_zero() {
+        let mut keys = KeySequence::default();
+        assert_eq!(feed(&mut keys, "0"), action(Action::LineStart, None, false));
+        assert_eq!(feed(&mut keys, "10j"), action(Action::Down, Some(10), false));
+        assert_eq!(feed(&mut keys, "g0"), action(Action::LineStart, None, false));
+    }
+
+    #[test]
+    fn test_broken_sequence() {
+        let mut keys = KeySequence::default();
+        assert_eq!(feed(&mut keys, "gj"), action(Action::Down, None, false));
+        assert_eq!(feed(&mut keys, "dgg"), action(Action::Top, None, false));
+        assert_eq!(feed(&mut keys, "gq"), None);
+        assert_eq!(keys.pending(), "");
+    }
+
+    #[test]
+    fn test_repeat() {
+        let mut keys = KeySequence::default();
+        assert_eq!(feed(&mut keys, "."), None);
+        assert_eq!(feed(&mut keys, "2dd"), action(Action::DeleteLine, Some(2), false));
+        // motions are not repeated
+        feed(&mut keys, "j");
+        assert_eq!(feed(&mut keys, "."), action(Action::DeleteLine, Some(2), true));
+        assert_eq!(feed(&mut keys, "5."), action(Action::DeleteLine, Some(5), true));
+    }
+
+    #[test]
+    fn test_may_change() {
+        let key = |ch| KeyEvent::new(KeyCode::Char(ch), KeyModifiers::NONE);
+        let mut keys = KeySequence::default();
+        assert!(keys.may_change(&key('x')));
+        assert!(!keys.may_change(&key('j')));
+        assert!(!keys.may_change(&key('d')));
+        assert!(!keys.may_change(&key('.')));
+        feed(&mut keys, "d");
+        assert!(keys.may_change(&key('d')));
+        feed(&mut keys, "d");
+        assert!(keys.may_change(&key('.')));
+        keys.set_mode(Mode::Insert);
+        assert!(keys.may_change(&key('j')));
+    }
+
+    #[test]
+    fn test_modes() {
+        let mut keys = KeySequence::default();
+        assert_eq!(feed(&mut keys, "v"), action(Action::Visual, None, false));
+        assert_eq!(keys.mode(), Mode::Visual);
+        assert_eq!(feed(&mut keys, "d"), action(Action::DeleteLine, None, false));
+        assert_eq!(keys.mode(), Mode::Normal);
+        feed(&mut keys, "vv");
+        assert_eq!(keys.mode(), Mode::Normal);
+
+        keys.set_mode(Mode::Insert);
+        let text = KeyEvent::new(KeyCode::Char('j'), KeyModifiers::NONE);
+        assert_eq!(keys.feed(&text), Some(Input::Text(text)));
+        let esc = KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE);
+        assert_eq!(keys.feed(&esc), action(Action::Cancel, None, false));
+        assert_eq!(keys.mode(), Mode::Normal);
+
+        // switching modes drops what was typed so far
+        feed(&mut keys, "3g");
+        keys.set_mode(Mode::Visual);
+        assert_eq!(keys.pending(), "");
+    }
+}
@@ -0,0 +1,144 @@
+use crossterm::event::{Event, KeyCode};
+use ratatui::layout::{Constraint, Flex, Layout, Position, Rect};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Clear, Paragraph, Wrap};
+use ratatui::Frame;
+use redo::todo::Todo;
+
+use crate::event::EventHandler;
+use crate::theme::Theme;
+
+/// The popup with the notes of a todo, they are edited in it unless the list is read-only.
+#[derive(Debug, Default)]
+pub struct Notes {
+    /// the todo of the selected list whose notes are shown
+    pub todo: usize,
+    /// any key closes the popup instead of editing the notes
+    pub read_only: bool,
+    lines: Vec<String>,
+    /// the line the cursor is on and the character in it
+    row: usize,
+    column: usize,
+}
+
+pub enum NotesState {
+    /// the popup was closed with the notes as they were left
+    Save(String),
+    Close,
+}
+
+impl EventHandler<(), NotesState> for Notes {
+    fn handle_event(&mut self, event: &Event, _: ()) -> Option<NotesState> {
+        let Event::Key(key) = event else {
+            return None;
+        };
+        if self.read_only {
+            return Some(NotesState::Close);
+        }
+
+        match key.code {
+            KeyCode::Esc => return Some(NotesState::Save(self.lines.join("\n"))),
+            KeyCode::Char(ch) => {
+                let idx = self.byte_index();
+                self.lines[self.row].insert(idx, ch);
+                self.column += 1;
+            }
+            KeyCode::Enter => {
+                let idx = self.byte_index();
+                let rest = self.lines[self.row].split_off(idx);
+                self.row += 1;
+                self.column = 0;
+                self.lines.insert(self.row, rest);
+            }
+            KeyCode::Backspace if self.column > 0 => {
+                self.column -= 1;
+                let idx = self.byte_index();
+                self.lines[self.row].remove(idx);
+            }
+            KeyCode::Backspace if self.row > 0 => {
+                let line = self.lines.remove(self.row);
+                self.row -= 1;
+                self.column = self.len();
+                self.lines[self.row].push_str(&line);
+            }
+            KeyCode::Delete if self.column < self.len() => {
+                let idx = self.byte_index();
+                self.lines[self.row].remove(idx);
+            }
+            KeyCode::Delete if self.row + 1 < self.lines.len() => {
+                let line = self.lines.remove(self.row + 1);
+                self.lines[self.row].push_str(&line);
+            }
+            KeyCode::Left => self.column = self.column.saturating_sub(1),
+            KeyCode::Right => self.column = usize::min(self.column + 1, self.len()),
+            KeyCode::Up => self.row = self.row.saturating_sub(1),
+            KeyCode::Down => self.row = usize::min(self.row + 1, self.lines.len() - 1),
+            KeyCode::Home => self.column = 0,
+            KeyCode::End => self.column = self.len(),
+            _ => {}
+        }
+        // up and down keep the column as far as the line they end up on goes
+        self.column = usize::min(self.column, self.len());
+        None
+    }
+}
+
+impl Notes {
+    /// the notes of the todo at `todo` with the cursor at their end
+    pub fn new(todo: usize, note: &str, read_only: bool) -> Self {
+        let lines = note.split('\n').map(str::to_string).collect::<Vec<_>>();
+        let row = lines.len() - 1;
+        let column = lines[row].chars().count();
+        Self {
+            todo,
+            read_only,
+            lines,
+            row,
+            column,
+        }
+    }
+
+    /// the popup in the middle of `area` with `todo` as its title
+    pub fn draw(&self, frame: &mut Frame, area: Rect, todo: &Todo, theme: &Theme) {
+        let [area] = Layout::vertical([Constraint::Percentage(60)])
+            .flex(Flex::Center)
+            .areas(area);
+        let [area] = Layout::horizontal([Constraint::Percentage(70)])
+            .flex(Flex::Center)
+            .areas(area);
+
+        let hint = match self.read_only {
+            true => " read-only ",
+            false => " <Esc> saves ",
+        };
+        let block = Block::bordered()
+            .border_style(theme.popup)
+            .title_top(Line::from(format!(" {} ", todo.data)).style(theme.title))
+            .title_bottom(Line::from(hint).right_aligned());
+        let inner = block.inner(area);
+        let notes = Paragraph::new(self.lines.join("\n")).style(theme.todo).block(block);
+        frame.render_widget(Clear, area);
+
+        if self.read_only {
+            frame.render_widget(notes.wrap(Wrap { trim: false }), area);
+            return;
+        }
+        // long notes scroll so the cursor stays inside the popup
+        let y = (self.row + 1).saturating_sub(inner.height as usize) as u16;
+        let x = (self.column + 1).saturating_sub(inner.width as usize) as u16;
+        frame.render_widget(notes.scroll((y, x)), area);
+        let position = Position::new(inner.x + self.column as u16 - x, inner.y + self.row as u16 - y);
+        frame.set_cursor_position(position);
+    }
+
+    /// the amount of characters in the line of the cursor
+    fn len(&self) -> usize {
+        self.lines[self.row].chars().count()
+    }
+
+    /// where the character the cursor is on starts in its line
+    fn byte_index(&self) -> usize {
+        let line = &self.lines[self.row];
+        line.char_indices().nth(self.column).map_or(line.len(), |(idx, _)| idx)
+    }
+}
@@ -0,0 +1,94 @@
+use std::ffi::OsString;
+use std::path::PathBuf;
+
+/// where the todos are kept, `$REDO_DATA_DIR`, `$XDG_DATA_HOME/redo` or `~/.local/share/redo`
+pub fn data_dir() -> Option<PathBuf> {
+    dir("REDO_DATA_DIR", "XDG_DATA_HOME", &[".local", "share"])
+}
+
+/// `$REDO_CONFIG_DIR`, `$XDG_CONFIG_HOME/redo` or `~/.config/redo`
+pub fn config_dir() -> Option<PathBuf> {
+    dir("REDO_CONFIG_DIR", "XDG_CONFIG_HOME", &[".config"])
+}
+
+/// where the log goes, `$REDO_STATE_DIR`, `$XDG_STATE_HOME/redo` or `~/.local/state/redo`
+pub fn state_dir() -> Option<PathBuf> {
+    dir("REDO_STATE_DIR", "XDG_STATE_HOME", &[".local", "state"])
+}
+
+/// the file opened when none is given
+pub fn default_file() -> Option<PathBuf> {
+    Some(data_dir()?.join("todos.todo"))
+}
+
+pub fn config_file() -> Option<PathBuf> {
+    Some(config_dir()?.join("config.toml"))
+}
+
+pub fn log_file() -> Option<PathBuf> {
+    Some(state_dir()?.join("redo.log"))
+}
+
+/// the directory in `var` as is, or the `redo` directory in the one in `xdg` or in `home` under `$HOME`, empty
+/// variables count as not set like the XDG spec asks
+fn dir(var: &str, xdg: &str, home: &[&str]) -> Option<PathBuf> {
+    dir_in(var, xdg, home, |name| std::env::var_os(name))
+}
+
+/// like [`dir`] with the variables looked up by `env`
+fn dir_in(var: &str, xdg: &str, home: &[&str], env: impl Fn(&str) -> Option<OsString>) -> Option<PathBuf> {
+    let set = |name: &str| env(name).filter(|value| !value.is_empty());
+    if let Some(dir) = set(var) {
+        return Some(PathBuf::from(dir));
+    }
+    let base = match set(xdg) {
+        Some(dir) => PathBuf::from(dir),
+        None => home
+            .iter()
+            .fold(PathBuf::from(set("HOME")?), |dir, part| dir.join(part)),
+    };
+    Some(base.join("redo"))
+}
+
+#[cfg(test)]
+mod test {
+    use std::ffi::OsString;
+    use std::path::PathBuf;
+
+    use super::dir_in;
+
+    fn data_dir(vars: &[(&str, &str)]) -> Option<PathBuf> {
+        let env = |name: &str| {
+            vars.iter()
+                .find(|(var, _)| *var == name)
+                .map(|(_, value)| OsString::from(value))
+        };
+        dir_in("REDO_DATA_DIR", "XDG_DATA_HOME", &[".local", "share"], env)
+    }
+
+    #[test]
+    fn test_dir() {
+        let home = ("HOME", "/home/me");
+        assert_eq!(data_dir(&[home]), Some(PathBuf::from("/home/me/.local/share/redo")));
+        assert_eq!(
+            data_dir(&[home, ("XDG_DATA_HOME", "/data")]),
+            Some(PathBuf::from("/data/redo"))
+        );
+        // the directory of redo itself is used as is and wins over the XDG one
+        assert_eq!(
+            data_dir(&[home, ("XDG_DATA_HOME", "/data"), ("REDO_DATA_DIR", "/todos")]),
+            Some(PathBuf::from("/todos"))
+        );
+        // empty variables are not set
+        assert_eq!(
+            data_dir(&[home, ("XDG_DATA_HOME", ""), ("REDO_DATA_DIR", "")]),
+            Some(PathBuf::from("/home/me/.local/share/redo"))
+        );
+        assert_eq!(
+            data_dir(&[("XDG_DATA_HOME", "/data")]),
+            Some(PathBuf::from("/data/redo"))
+        );
+        assert_eq!(data_dir(&[]), None);
+        assert_eq!(data_dir(&[("HOME", "")]), None);
+    }
+}
@@ -0,0 +1,52 @@
+use std::path::{Path, PathBuf};
+
+use redo::parser;
+use redo::todo::TodoListCollection;
+
+use crate::paths;
+
+/// Edits of a file that were not saved when the app crashed, found the next time the file is opened.
+#[derive(Debug)]
+pub struct Recovery {
+    /// the tab of the file
+    pub tab: usize,
+    pub path: PathBuf,
+    pub collection: TodoListCollection,
+}
+
+/// where the unsaved edits of `file` are kept, every file gets its own so tabs do not overwrite each other
+pub fn path(file: &str) -> Option<PathBuf> {
+    let name = match file.is_empty() {
+        true => "[no name]".to_string(),
+        false => std::path::absolute(file)
+            .ok()?
+            .to_string_lossy()
+            .replace(['/', '\\'], "%"),
+    };
+    Some(paths::state_dir()?.join("recovery").join(name))
+}
+
+/// writes the collection next to the other recovery files, returning where it went
+pub fn write(file: &str, collection: &TodoListCollection) -> Result<PathBuf, String> {
+    let path = path(file).ok_or("there is no state directory to write to")?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("could not create {}: {e}", dir.display()))?;
+    }
+    std::fs::write(&path, parser::serialize(collection))
+        .map_err(|e| format!("could not write {}: {e}", path.display()))?;
+    Ok(path)
+}
+
+/// the edits a crash left behind for the file open in `tab`
+pub fn find(tab: usize, file: &str) -> Option<Recovery> {
+    let path = path(file)?;
+    let content = std::fs::read_to_string(&path).ok()?;
+    let collection = parser::parse_collection(&content).ok()?;
+    Some(Recovery { tab, path, collection })
+}
+
+pub fn remove(path: &Path) {
+    if let Err(e) = std::fs::remove_file(path) {
+        tracing::info!("could not remove {}: {e}", path.display());
+    }
+}
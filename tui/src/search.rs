@@ -0,0 +1,299 @@
+use std::ops::Range;
+
+use crossterm::event::{Event, KeyCode};
+use ratatui::layout::Rect;
+use ratatui::style::{Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Clear, List, ListItem, Paragraph};
+use ratatui::Frame;
+use redo::query::Query;
+use redo::todo::{Todo, TodoListCollection, TodoStatus};
+use redo::TodoList;
+
+use crate::event::EventHandler;
+use crate::theme::Theme;
+
+/// The `/` search, while it has a query the selection bar and the editor only show what matches it.
+/// The query is a [`Query`] so besides text it can filter on things like `status:open` or `due<7d`.
+#[derive(Debug, Default)]
+pub struct Search {
+    pub query: String,
+    /// whether the query is being typed
+    pub editing: bool,
+
+    /// the query from before editing started, restored when editing is cancelled
+    previous: String,
+    filter: Query,
+    /// why the query could not be parsed, nothing matches until it is fixed
+    error: Option<String>,
+}
+
+pub enum SearchState {
+    /// the query changed while typing
+    Changed,
+    Confirmed,
+    Cancelled,
+}
+
+impl EventHandler<(), SearchState> for Search {
+    fn handle_event(&mut self, event: &Event, _: ()) -> Option<SearchState> {
+        let Event::Key(key) = event else {
+            return None;
+        };
+        match key.code {
+            KeyCode::Char(ch) => {
+                self.query.push(ch);
+                self.compile();
+                Some(SearchState::Changed)
+            }
+            KeyCode::Backspace => {
+                if self.query.pop().is_none() {
+                    self.editing = false;
+                    return Some(SearchState::Cancelled);
+                }
+                self.compile();
+                Some(SearchState::Changed)
+            }
+            KeyCode::Enter => {
+                self.editing = false;
+                Some(SearchState::Confirmed)
+            }
+            KeyCode::Esc => {
+                self.editing = false;
+                self.query = std::mem::take(&mut self.previous);
+                self.compile();
+                Some(SearchState::Cancelled)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// how many bytes at the start of `text` match `pattern`, lowercasing the characters of `text` when ignoring case
+fn match_len(text: &str, pattern: &str, ignore_case: bool) -> Option<usize> {
+    let mut pattern = pattern.chars().peekable();
+    for (idx, ch) in text.char_indices() {
+        if pattern.peek().is_none() {
+            return Some(idx);
+        }
+        let lowered = match ignore_case {
+            true => ch.to_lowercase().collect::<Vec<_>>(),
+            false => vec![ch],
+        };
+        // a character only matches as a whole
+        for ch in lowered {
+            if pattern.next() != Some(ch) {
+                return None;
+            }
+        }
+    }
+    pattern.peek().is_none().then_some(text.len())
+}
+
+impl Search {
+    pub fn start(&mut self) {
+        self.previous = std::mem::take(&mut self.query);
+        self.editing = true;
+        self.compile();
+    }
+
+    pub fn clear(&mut self) {
+        self.query.clear();
+        self.previous.clear();
+        self.editing = false;
+        self.compile();
+    }
+
+    pub fn is_active(&self) -> bool {
+        !self.query.is_empty()
+    }
+
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    fn compile(&mut self) {
+        match self.query.parse() {
+            Ok(filter) => {
+                self.filter = filter;
+                self.error = None;
+            }
+            Err(e) => self.error = Some(e),
+        }
+    }
+
+    /// the parsed query, `None` while it has an error
+    fn filter(&self) -> Option<&Query> {
+        match self.error {
+            Some(_) => None,
+            None => Some(&self.filter),
+        }
+    }
+
+    /// the byte ranges of `text` matching the text of the query, which ignores case unless it has uppercase
+    /// letters in it
+    pub fn find(&self, text: &str) -> Vec<Range<usize>> {
+        let Some(query) = self.filter() else {
+            return vec![];
+        };
+        let mut ranges = vec![];
+        for pattern in query.text() {
+            let ignore_case = !pattern.chars().any(char::is_uppercase);
+            let mut start = 0;
+            // every character is lowercased on its own, so the ranges stay in `text` even when lowercasing
+            // changes the length of a character
+            while let Some((idx, _)) = text[start..].char_indices().next() {
+                let at = start + idx;
+                match match_len(&text[at..], pattern, ignore_case) {
+                    Some(len) if len > 0 => {
+                        ranges.push(at..at + len);
+                        start = at + len;
+                    }
+                    _ => start = at + text[at..].chars().next().map_or(1, char::len_utf8),
+                }
+            }
+        }
+        ranges.sort_by_key(|range| range.start);
+        ranges
+    }
+
+    pub fn matches(&self, list: &TodoList, todo: &Todo) -> bool {
+        self.filter().is_some_and(|query| query.matches(list, todo))
+    }
+
+    /// the todos of `list` that match, `None` when there is nothing to filter by
+    pub fn rows(&self, list: &TodoList) -> Option<Vec<usize>> {
+        if !self.is_active() {
+            return None;
+        }
+        let rows = list
+            .data
+            .iter()
+            .enumerate()
+            .filter(|(_, todo)| self.matches(list, todo))
+            .map(|(idx, _)| idx)
+            .collect();
+        Some(rows)
+    }
+
+    /// the lists whose title or todos match, `None` when there is nothing to filter by
+    pub fn list_rows(&self, collection: &TodoListCollection) -> Option<Vec<usize>> {
+        if !self.is_active() {
+            return None;
+        }
+        let Some(query) = self.filter() else {
+            return Some(vec![]);
+        };
+        let rows = collection
+            .lists
+            .iter()
+            .enumerate()
+            .filter(|(_, list)| {
+                query.matches_title(&list.title) || list.data.iter().any(|todo| query.matches(list, todo))
+            })
+            .map(|(idx, _)| idx)
+            .collect();
+        Some(rows)
+    }
+
+    /// every matching todo in the collection as `(list, todo)`
+    pub fn results(&self, collection: &TodoListCollection) -> Vec<(usize, usize)> {
+        match self.filter() {
+            Some(query) if self.is_active() => collection
+                .query(query)
+                .into_iter()
+                .map(|found| (found.list_index, found.todo_index))
+                .collect(),
+            _ => vec![],
+        }
+    }
+
+    /// `text` with every match styled as `matched`
+    pub fn highlight<'a>(&self, text: &'a str, style: Style, matched: Style) -> Line<'a> {
+        let mut spans = vec![];
+        let mut last = 0;
+        for range in self.find(text) {
+            if range.start < last {
+                continue;
+            }
+            spans.push(Span::styled(&text[last..range.start], style));
+            spans.push(Span::styled(&text[range.clone()], matched));
+            last = range.end;
+        }
+        spans.push(Span::styled(&text[last..], style));
+        Line::from(spans)
+    }
+
+    /// the bottom line of `area` showing the query being typed
+    pub fn draw_prompt(&self, frame: &mut Frame, area: Rect) {
+        let area = Self::prompt_area(area);
+        frame.render_widget(Clear, area);
+        let mut prompt = Line::from(format!("/{}", self.query));
+        if let Some(error) = self.error() {
+            prompt.push_span(Span::from(format!("  {error}")).dim());
+        }
+        frame.render_widget(Paragraph::new(prompt), area);
+    }
+
+    pub fn prompt_area(area: Rect) -> Rect {
+        Rect {
+            y: area.bottom().saturating_sub(1),
+            height: u16::min(area.height, 1),
+            ..area
+        }
+    }
+
+    /// every match in the collection together with the list it is in
+    pub fn draw_results(&self, frame: &mut Frame, area: Rect, collection: &TodoListCollection, theme: &Theme) {
+        let mut items = vec![];
+        for (list_idx, todo_idx) in self.results(collection) {
+            let list = &collection.lists[list_idx];
+            let todo = &list.data[todo_idx];
+            let style = match todo.status {
+                TodoStatus::Complete => theme.completed,
+                TodoStatus::Incomplete => theme.todo,
+            };
+            let mut line = self.highlight(&todo.data, style, theme.matched);
+            line.spans.insert(0, Span::styled(format!("{} ", todo.status), style));
+            line.spans
+                .insert(0, Span::styled(format!("{} ", list.title), theme.title));
+            items.push(ListItem::new(line));
+        }
+
+        let title = Line::from(format!("Results /{} ({})", self.query, items.len())).style(theme.title);
+        let results = List::new(items).block(Block::bordered().border_style(theme.editor_border).title_top(title));
+        frame.render_widget(results, area);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn search(query: &str) -> Search {
+        let mut search = Search {
+            query: query.to_string(),
+            ..Default::default()
+        };
+        search.compile();
+        search
+    }
+
+    #[test]
+    fn test_find() {
+        assert_eq!(search("milk").find("buy Milk and milk"), vec![4..8, 13..17]);
+        assert_eq!(search("Milk").find("buy Milk and milk"), vec![4..8]);
+        assert_eq!(search("status:open").find("milk"), vec![]);
+
+        // a dotted capital I gets longer and the ohm sign shorter when lowercased, the ranges still have to fall
+        // on their characters
+        let text = "\u{130}x \u{2126}milk";
+        let ranges = search("milk").find(text);
+        assert_eq!(ranges, vec![7..11]);
+        assert_eq!(&text[ranges[0].clone()], "milk");
+        assert_eq!(search("\u{3c9}").find(text), vec![4..7]);
+        assert_eq!(search("i\u{307}").find(text), vec![0..2]);
+        assert_eq!(search("i\u{307}x").find(text), vec![0..3]);
+        search("x").highlight(text, Style::default(), Style::default());
+    }
+}
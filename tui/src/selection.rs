@@ -1,18 +1,28 @@
 use crossterm::event::{Event, KeyCode};
-use ratatui::layout::{Margin, Rect};
-use ratatui::style::{Style, Stylize};
-use ratatui::text::Line;
+use ratatui::layout::{Margin, Position, Rect};
+use ratatui::style::Stylize;
+use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Clear, List, ListDirection, Paragraph};
 use ratatui::Frame;
+use redo::todo::Stats;
+use unicode_width::UnicodeWidthStr;
 
 use crate::cursor::{Cursor, CursorMovement};
 use crate::event::EventHandler;
+use crate::keymap::Action;
+use crate::modal::{Input, KeySequence, Mode};
+use crate::search::Search;
+use crate::theme::Theme;
 use crate::viewport::Viewport;
 
 #[derive(Debug, Default)]
 pub struct SelectionBar {
     pub buffer: String,
-    pub viewport: Viewport,
+    /// the size inside the borders
+    viewport: Viewport,
+    pub keys: KeySequence,
+    /// lists can not be added, removed or edited
+    pub read_only: bool,
 
     popup_mode: bool,
     cursor: Cursor,
@@ -27,101 +37,181 @@ pub enum SelectionState {
     Remove(usize),
     AddTodo(String),
     Show(usize),
+    Undo(u16),
+    Redo(u16),
+    Search,
+    NextMatch(u16),
+    PrevMatch(u16),
+    ClearSearch,
+    Command,
+    /// edit the list in `$EDITOR`
+    External(usize),
+    /// go to the next tab, or to the tab with that number
+    NextTab(Option<u16>),
+    PrevTab(u16),
+    /// the action would change a collection that is read-only
+    ReadOnly,
 }
 
 impl EventHandler<&Vec<String>, SelectionState> for SelectionBar {
     fn handle_event(&mut self, event: &Event, names: &Vec<String>) -> Option<SelectionState> {
-        if let Event::Key(key) = event {
-            if self.popup_mode {
-                match key.code {
-                    KeyCode::Char(ch) => {
-                        self.buffer.push(ch);
-                    }
-
-                    KeyCode::Backspace => {
-                        let _ = self.buffer.pop();
-                    }
-
-                    KeyCode::Enter => {
-                        if self.buffer.is_empty() {
-                            return None;
-                        }
-                        let title = "[".to_string() + &self.buffer + "]";
-                        self.popup_mode = false;
-                        self.buffer.clear();
-                        return Some(SelectionState::AddTodo(title));
-                    }
-
-                    KeyCode::Esc => {
-                        self.popup_mode = false;
-                        return Some(SelectionState::DelPopup);
-                    }
-
-                    _ => {}
-                }
-            }
+        let Event::Key(key) = event else {
+            return None;
+        };
 
+        if self.popup_mode {
             match key.code {
-                KeyCode::Up | KeyCode::Char('k') => {
-                    self.move_up(1);
-                    return Some(SelectionState::Show(self.cursor.y as usize));
-                }
-                KeyCode::Down | KeyCode::Char('j') => {
-                    self.move_down(1, names.len().saturating_sub(1) as u16);
-                    return Some(SelectionState::Show(self.cursor.y as usize));
+                KeyCode::Char(ch) => {
+                    self.buffer.push(ch);
                 }
 
-                KeyCode::Char(' ') => {
-                    if !names.is_empty() {
-                        return Some(SelectionState::Selected(self.cursor.y as usize));
-                    }
-                }
-                KeyCode::Char('x') => {
-                    let state = Some(SelectionState::Remove(self.cursor.y as usize));
-                    //self.remove_name(self.cursor.y as usize);
-                    if self.cursor.y as usize > names.len().saturating_sub(1) {
-                        self.cursor.y = self.cursor.y.saturating_sub(1);
-                    }
-                    return state;
+                KeyCode::Backspace => {
+                    let _ = self.buffer.pop();
                 }
+
                 KeyCode::Enter => {
-                    self.popup_mode = true;
-                    return Some(SelectionState::AddPopup);
+                    if self.buffer.is_empty() {
+                        return None;
+                    }
+                    let title = "[".to_string() + &self.buffer + "]";
+                    self.popup_mode = false;
+                    self.buffer.clear();
+                    return Some(SelectionState::AddTodo(title));
                 }
 
                 KeyCode::Esc => {
-                    if self.popup_mode {
-                        self.popup_mode = false
-                    }
+                    self.popup_mode = false;
+                    return Some(SelectionState::DelPopup);
                 }
 
                 _ => {}
             }
+            return None;
+        }
+
+        let Input::Action { action, count, .. } = self.keys.feed(key)? else {
+            return None;
+        };
+        let amount = count.unwrap_or(1);
+        // toggle opens the list here instead of changing it
+        if self.read_only && action.edits() && action != Action::Toggle {
+            return Some(SelectionState::ReadOnly);
+        }
+
+        match action {
+            Action::Up => {
+                self.move_up(amount);
+                return Some(SelectionState::Show(self.line()));
+            }
+            Action::Down => {
+                self.move_down(amount, names.len().saturating_sub(1) as u16);
+                return Some(SelectionState::Show(self.line()));
+            }
+            Action::Top | Action::Bottom => {
+                let last = names.len().saturating_sub(1);
+                let line = match (action, count) {
+                    (_, Some(count)) => count.saturating_sub(1) as usize,
+                    (Action::Top, None) => 0,
+                    _ => last,
+                };
+                self.goto(usize::min(line, last));
+                return Some(SelectionState::Show(self.line()));
+            }
+
+            Action::Toggle if !names.is_empty() => {
+                return Some(SelectionState::Selected(self.line()));
+            }
+            Action::Remove | Action::DeleteLine => {
+                if names.is_empty() {
+                    return None;
+                }
+                let state = Some(SelectionState::Remove(self.line()));
+                if self.line() >= names.len().saturating_sub(1) {
+                    self.move_up(1);
+                }
+                return state;
+            }
+            Action::Confirm | Action::OpenBelow | Action::OpenAbove => {
+                self.popup_mode = true;
+                return Some(SelectionState::AddPopup);
+            }
+            Action::Undo => return Some(SelectionState::Undo(amount)),
+            Action::Redo => return Some(SelectionState::Redo(amount)),
+            Action::Search => return Some(SelectionState::Search),
+            Action::NextMatch => return Some(SelectionState::NextMatch(amount)),
+            Action::PrevMatch => return Some(SelectionState::PrevMatch(amount)),
+            Action::Cancel => return Some(SelectionState::ClearSearch),
+            Action::Command => return Some(SelectionState::Command),
+            Action::NextTab => return Some(SelectionState::NextTab(count)),
+            Action::PrevTab => return Some(SelectionState::PrevTab(amount)),
+            Action::External if !names.is_empty() => return Some(SelectionState::External(self.line())),
+            // the selection bar only works on whole lists so there is nothing to select
+            Action::Visual => self.keys.set_mode(Mode::Normal),
+
+            _ => {}
         }
         None
     }
 }
 
 impl SelectionBar {
-    pub fn draw(&mut self, frame: &mut Frame, selection_area: Rect, names: &[String]) {
+    /// whether `event` could change the lists, confirming the popup adds one
+    pub fn may_change(&self, event: &Event) -> bool {
+        match event {
+            Event::Key(key) if self.popup_mode => key.code == KeyCode::Enter,
+            Event::Key(key) => self.keys.may_change(key),
+            _ => false,
+        }
+    }
+
+    /// draws the names with how far along each list is, `stats` goes with `names`
+    pub fn draw(
+        &mut self,
+        frame: &mut Frame,
+        selection_area: Rect,
+        names: &[String],
+        stats: &[Stats],
+        search: &Search,
+        theme: &Theme,
+    ) {
+        let width = selection_area.width.saturating_sub(2) as usize;
         let mut names_vec = vec![];
-        for item in names.iter().skip(self.scroll as usize).take(self.viewport.y() as usize) {
-            names_vec.push(item.clone());
+        for (item, stats) in names
+            .iter()
+            .zip(stats)
+            .skip(self.scroll as usize)
+            .take(self.height() as usize)
+        {
+            let style = match stats.is_complete() {
+                true => theme.list.dim(),
+                false => theme.list,
+            };
+            let mut line = search.highlight(item, style, theme.matched);
+            // the progress goes to the right edge when there is room for it next to the name
+            let progress = progress(stats);
+            // measured in columns, wide characters like CJK take two
+            let used = item.width() + progress.width();
+            if used < width {
+                line.spans
+                    .push(Span::styled(format!("{}{progress}", " ".repeat(width - used)), style));
+            }
+            names_vec.push(line);
         }
 
-        let title = Line::from("Selection").style(Style::default().yellow());
+        let title = match search.is_active() {
+            true => Line::from(format!("Selection /{}", search.query)),
+            false => Line::from("Selection"),
+        };
+        let title = title.style(theme.title);
         let list = List::new(names_vec)
             .direction(ListDirection::TopToBottom)
-            .block(Block::bordered().red().title_top(title))
-            .white();
+            .block(Block::bordered().border_style(theme.selection_border).title_top(title))
+            .style(theme.list);
         frame.render_widget(list, selection_area);
     }
 
-    pub fn draw_popup(&self, frame: &mut Frame) {
-        let popup = Block::bordered()
-            .style(Style::default())
-            .title_top("Adding TodoList")
-            .blue();
+    pub fn draw_popup(&self, frame: &mut Frame, theme: &Theme) {
+        let popup = Block::bordered().style(theme.popup).title_top("Adding TodoList");
         let text = Paragraph::new(&*self.buffer).block(popup);
 
         let area = frame.area().inner(Margin {
@@ -142,28 +232,85 @@ impl SelectionBar {
     pub fn cursor_pos(&self) -> (u16, u16) {
         (self.cursor.y, self.cursor.x)
     }
+
+    /// the index into the shown names of the list under the cursor
+    pub fn line(&self) -> usize {
+        (self.scroll + self.cursor.y) as usize
+    }
+
+    pub fn is_typing(&self) -> bool {
+        self.popup_mode
+    }
+
+    /// drops the popup and what was typed into it
+    pub fn reset(&mut self) {
+        self.popup_mode = false;
+        self.buffer.clear();
+        self.keys.set_mode(Mode::Normal);
+    }
+
+    pub fn goto(&mut self, line: usize) {
+        let height = self.height();
+        self.cursor.scroll_to(line as u16, &mut self.scroll, height);
+    }
+
+    /// the row of the `len` names at `position` when the selection bar is drawn in `area`
+    pub fn row_at(&self, area: Rect, position: Position, len: usize) -> Option<usize> {
+        let inner = area.inner(Margin::new(1, 1));
+        if !inner.contains(position) {
+            return None;
+        }
+        let row = (self.scroll + position.y - inner.y) as usize;
+        (row < len).then_some(row)
+    }
+
+    /// scrolls the `len` names by `amount` lines, down when it is positive
+    pub fn scroll_by(&mut self, amount: i32, len: usize) {
+        let height = self.height();
+        self.cursor.scroll_by(amount, &mut self.scroll, height, len as u16);
+    }
+
+    /// fits the selection bar into `area`, keeping the cursor on one of the `len` names in view
+    pub fn resize(&mut self, area: Rect, len: usize) {
+        let inner = area.inner(Margin::new(1, 1));
+        self.viewport = Viewport::new(inner.height, inner.width);
+        let height = self.height();
+        self.cursor.clamp(&mut self.scroll, height, len as u16);
+    }
+
+    /// the amount of names that fit inside the borders of the selection bar
+    fn height(&self) -> u16 {
+        self.viewport.y()
+    }
+}
+
+/// the done and all todos and a gauge of them, like `3/7 ■■□□`
+fn progress(stats: &Stats) -> String {
+    const CELLS: usize = 4;
+    // rounded down so only finished lists get a full gauge
+    let filled = match stats.total {
+        0 => 0,
+        total => stats.done * CELLS / total,
+    };
+    format!(
+        "{}/{} {}{}",
+        stats.done,
+        stats.total,
+        "■".repeat(filled),
+        "□".repeat(CELLS - filled)
+    )
 }
 
 impl CursorMovement for SelectionBar {
     fn move_up(&mut self, amount: u16) {
-        if self.cursor.y == 0 {
-            self.scroll = self.scroll.saturating_sub(1);
-        }
-        self.cursor.y = self.cursor.y.saturating_sub(amount);
+        let line = self.line().saturating_sub(amount as usize);
+        self.goto(line);
         tracing::debug!("selection_bar: move_up {:?}", self.cursor);
     }
 
     fn move_down(&mut self, amount: u16, max: u16) {
-        self.cursor.y = u16::min(self.cursor.y + amount, max);
-        let min = u16::min(max, self.viewport.y());
-        if self.cursor.y + amount < min.saturating_sub(1) {
-            self.cursor.y += amount;
-        }
-        if self.cursor.y >= self.viewport.y().saturating_sub(2) && self.cursor.y + self.scroll <= max.saturating_sub(1)
-        {
-            self.scroll += 1;
-        }
-
+        let line = u16::min((self.line() as u16).saturating_add(amount), max);
+        self.goto(line as usize);
         tracing::debug!("selection_bar move_down: {:?}", self.cursor);
     }
 }
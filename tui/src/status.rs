@@ -0,0 +1,60 @@
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::Stylize;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::Paragraph;
+use ratatui::Frame;
+
+use crate::command::Message;
+use crate::theme::Theme;
+
+/// What the bar at the bottom of the screen shows.
+#[derive(Debug, Default)]
+pub struct Status {
+    pub mode: String,
+    pub file: String,
+    pub dirty: bool,
+    pub read_only: bool,
+    /// how the editor shows the todos when it does not show the file as it is
+    pub view: Vec<String>,
+    /// the completed and all todos of the selected list
+    pub progress: Option<(usize, usize)>,
+    /// the row of the cursor counting from 1 and the amount of rows
+    pub position: Option<(usize, usize)>,
+    /// what the last key did, until the next one
+    pub message: Option<Message>,
+}
+
+pub fn draw(frame: &mut Frame, area: Rect, status: &Status, theme: &Theme) {
+    let mut left = vec![
+        Span::styled(format!(" {} ", status.mode), theme.status.reversed().bold()),
+        Span::styled(format!(" {}", status.file), theme.status),
+    ];
+    if status.dirty {
+        left.push(Span::styled(" [+]", theme.status.bold()));
+    }
+    if status.read_only {
+        left.push(Span::styled(" [read-only]", theme.status));
+    }
+    left.push(Span::styled(" ", theme.status));
+    if let Some(message) = &status.message {
+        let style = match message.error {
+            true => theme.error,
+            false => theme.status,
+        };
+        left.push(Span::styled(format!(" {} ", message.text), style));
+    }
+
+    let mut right = status.view.iter().map(|view| format!("{view}  ")).collect::<String>();
+    if let Some((done, total)) = status.progress {
+        right.push_str(&format!("{done}/{total} done  "));
+    }
+    if let Some((row, rows)) = status.position {
+        right.push_str(&format!("{row}:{rows} "));
+    }
+
+    let right = Line::from(right).right_aligned();
+    let [left_area, right_area] =
+        Layout::horizontal([Constraint::Fill(1), Constraint::Length(right.width() as u16)]).areas(area);
+    frame.render_widget(Paragraph::new(Line::from(left)).style(theme.status), left_area);
+    frame.render_widget(Paragraph::new(right).style(theme.status), right_area);
+}
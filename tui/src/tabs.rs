@@ -0,0 +1,116 @@
+use std::fs::OpenOptions;
+use std::path::Path;
+
+use ratatui::layout::Rect;
+use ratatui::text::{Line, Span};
+use ratatui::widgets::Paragraph;
+use ratatui::Frame;
+use redo::todo::TodoListCollection;
+use redo::{filesystem, parser};
+
+use crate::history::History;
+use crate::theme::Theme;
+
+/// A file open in a tab. The tab being looked at keeps its state in `Interface` while it is shown.
+#[derive(Debug, Default)]
+pub struct Tab {
+    pub file: String,
+    pub collection: TodoListCollection,
+    /// the collection as it was last read or written, the tab has unsaved changes when they differ
+    pub saved: TodoListCollection,
+    pub history: History,
+    pub selected_list: usize,
+    /// the file is never written
+    pub read_only: bool,
+}
+
+impl Tab {
+    pub fn new(file: String, collection: TodoListCollection) -> Self {
+        Self {
+            file,
+            saved: collection.clone(),
+            collection,
+            ..Default::default()
+        }
+    }
+
+    /// reads `file`, a file that does not exist yet is an empty collection
+    pub fn open(file: String) -> Result<Self, String> {
+        let content = match std::fs::read_to_string(&file) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(format!("could not read {file}: {e}")),
+        };
+        let collection = parse(&file, &content)?;
+        let read_only = !is_writable(&file);
+        Ok(Self {
+            read_only,
+            ..Self::new(file, collection)
+        })
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        self.collection != self.saved
+    }
+
+    pub fn save(&mut self) -> Result<(), String> {
+        write(&self.file, &self.collection)?;
+        self.saved = self.collection.clone();
+        Ok(())
+    }
+}
+
+/// the collection in the `content` of `file`, an empty file is an empty collection
+pub fn parse(file: &str, content: &str) -> Result<TodoListCollection, String> {
+    match content.trim().is_empty() {
+        true => Ok(TodoListCollection::default()),
+        false => parser::parse_collection(content).map_err(|e| format!("could not open {file}: {e}")),
+    }
+}
+
+/// whether `file` can be written to, a file that does not exist yet is taken to be writable
+pub fn is_writable(file: &str) -> bool {
+    match std::fs::metadata(file) {
+        Ok(_) => OpenOptions::new().append(true).open(file).is_ok(),
+        Err(_) => true,
+    }
+}
+
+/// writes `collection` to `file`
+pub fn write(file: &str, collection: &TodoListCollection) -> Result<(), String> {
+    if file.is_empty() {
+        return Err("no file name, use :write <file>".to_string());
+    }
+    match filesystem::write(file, parser::serialize(collection)) {
+        true => Ok(()),
+        false => Err(format!("could not write {file}")),
+    }
+}
+
+/// the name a tab is shown with, the file name without its directories
+pub fn name(file: &str) -> &str {
+    match file.is_empty() {
+        true => "[no name]",
+        false => Path::new(file)
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or(file),
+    }
+}
+
+/// the names of the tabs in a line, `+` marks tabs with unsaved changes
+pub fn draw(frame: &mut Frame, area: Rect, tabs: &[(&str, bool)], active: usize, theme: &Theme) {
+    let spans = tabs
+        .iter()
+        .enumerate()
+        .map(|(idx, (name, dirty))| {
+            let style = match idx == active {
+                true => theme.selected,
+                false => theme.list,
+            };
+            let dirty = if *dirty { " +" } else { "" };
+            Span::styled(format!(" {} {name}{dirty} ", idx + 1), style)
+        })
+        .collect::<Vec<_>>();
+    frame.render_widget(Paragraph::new(Line::from(spans)), area);
+}
@@ -0,0 +1,233 @@
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use ratatui::style::{Color, Modifier, Style, Stylize};
+use serde::Deserialize;
+
+/// The named styles every widget draws with.
+#[derive(Debug, Clone)]
+pub struct Theme {
+    pub editor_border: Style,
+    pub selection_border: Style,
+    pub title: Style,
+    pub todo: Style,
+    pub completed: Style,
+    /// open todos waiting for other todos
+    pub blocked: Style,
+    pub list: Style,
+    pub selected: Style,
+    /// the parts of todos matching the search
+    pub matched: Style,
+    pub popup: Style,
+    /// messages about things that went wrong
+    pub error: Style,
+    pub help: Style,
+    pub help_header: Style,
+    /// the bar at the bottom of the screen
+    pub status: Style,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            editor_border: Style::new().white(),
+            selection_border: Style::new().red(),
+            title: Style::new().yellow(),
+            todo: Style::new().blue(),
+            completed: Style::new().blue().dim(),
+            blocked: Style::new().dark_gray().italic(),
+            list: Style::new().white(),
+            selected: Style::new().reversed(),
+            matched: Style::new().black().on_yellow(),
+            popup: Style::new().green(),
+            error: Style::new().red().bold(),
+            help: Style::new().blue(),
+            help_header: Style::new()
+                .fg(Color::Rgb(255, 255, 255))
+                .bg(Color::Rgb(183, 72, 101))
+                .bold(),
+            status: Style::new().white().on_dark_gray(),
+        }
+    }
+}
+
+/// `[theme]` in the config file, a built-in theme to start from and styles overriding parts of it
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct ThemeFile {
+    name: Option<String>,
+    #[serde(flatten)]
+    styles: BTreeMap<String, StyleFile>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct StyleFile {
+    fg: Option<String>,
+    bg: Option<String>,
+    modifiers: Vec<String>,
+}
+
+impl Theme {
+    pub const BUILTIN: [&'static str; 3] = ["default", "high-contrast", "monochrome"];
+
+    pub fn builtin(name: &str) -> Option<Self> {
+        let theme = match name {
+            "default" => Self::default(),
+            "high-contrast" => Self {
+                editor_border: Style::new().white().bold(),
+                selection_border: Style::new().light_yellow().bold(),
+                title: Style::new().light_yellow().bold(),
+                todo: Style::new().white(),
+                completed: Style::new().light_green(),
+                blocked: Style::new().light_magenta().italic(),
+                list: Style::new().white(),
+                selected: Style::new().black().on_light_yellow(),
+                matched: Style::new().black().on_light_cyan(),
+                popup: Style::new().white().on_black().bold(),
+                error: Style::new().light_red().bold(),
+                help: Style::new().white(),
+                help_header: Style::new().black().on_white().bold(),
+                status: Style::new().black().on_white(),
+            },
+            "monochrome" => Self {
+                editor_border: Style::new(),
+                selection_border: Style::new(),
+                title: Style::new().bold(),
+                todo: Style::new(),
+                completed: Style::new().dim().crossed_out(),
+                blocked: Style::new().italic(),
+                list: Style::new(),
+                selected: Style::new().reversed(),
+                matched: Style::new().bold().underlined(),
+                popup: Style::new().bold(),
+                error: Style::new().bold().reversed(),
+                help: Style::new(),
+                help_header: Style::new().reversed().bold(),
+                status: Style::new().reversed(),
+            },
+            _ => return None,
+        };
+        Some(theme)
+    }
+
+    pub fn from_file(file: ThemeFile) -> Result<Self, String> {
+        let name = file.name.as_deref().unwrap_or("default");
+        let mut theme = Self::builtin(name)
+            .ok_or_else(|| format!("unknown theme `{name}`, expected one of {}", Self::BUILTIN.join(", ")))?;
+
+        for (name, style) in file.styles {
+            let Some(target) = theme.style_mut(&name) else {
+                return Err(format!("unknown style `{name}` in theme"));
+            };
+            *target = style.parse()?;
+        }
+        Ok(theme)
+    }
+
+    /// the same theme without any colors, see <https://no-color.org>. The modifiers of the monochrome theme are
+    /// added so what only a color set apart, like search matches and the status bar, stays distinguishable
+    pub fn without_colors(mut self) -> Self {
+        let mut monochrome = Self::builtin("monochrome").expect("monochrome is a builtin theme");
+        for name in Self::STYLES {
+            let modifiers = monochrome
+                .style_mut(name)
+                .map_or(Modifier::empty(), |style| style.add_modifier);
+            if let Some(style) = self.style_mut(name) {
+                style.fg = None;
+                style.bg = None;
+                style.add_modifier |= modifiers;
+            }
+        }
+        self
+    }
+
+    /// whether colors should be turned off because `NO_COLOR` is set
+    pub fn no_color() -> bool {
+        std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty())
+    }
+
+    const STYLES: [&'static str; 14] = [
+        "editor_border",
+        "selection_border",
+        "title",
+        "todo",
+        "completed",
+        "blocked",
+        "list",
+        "selected",
+        "matched",
+        "popup",
+        "error",
+        "help",
+        "help_header",
+        "status",
+    ];
+
+    fn style_mut(&mut self, name: &str) -> Option<&mut Style> {
+        let style = match name {
+            "editor_border" => &mut self.editor_border,
+            "selection_border" => &mut self.selection_border,
+            "title" => &mut self.title,
+            "todo" => &mut self.todo,
+            "completed" => &mut self.completed,
+            "blocked" => &mut self.blocked,
+            "list" => &mut self.list,
+            "selected" => &mut self.selected,
+            "matched" => &mut self.matched,
+            "popup" => &mut self.popup,
+            "error" => &mut self.error,
+            "help" => &mut self.help,
+            "help_header" => &mut self.help_header,
+            "status" => &mut self.status,
+            _ => return None,
+        };
+        Some(style)
+    }
+}
+
+impl StyleFile {
+    fn parse(&self) -> Result<Style, String> {
+        let color = |color: &str| Color::from_str(color).map_err(|_| format!("unknown color `{color}`"));
+
+        let mut style = Style::new();
+        if let Some(fg) = &self.fg {
+            style = style.fg(color(fg)?);
+        }
+        if let Some(bg) = &self.bg {
+            style = style.bg(color(bg)?);
+        }
+        for modifier in &self.modifiers {
+            let modifier = match modifier.as_str() {
+                "bold" => Modifier::BOLD,
+                "dim" => Modifier::DIM,
+                "italic" => Modifier::ITALIC,
+                "underlined" => Modifier::UNDERLINED,
+                "reversed" => Modifier::REVERSED,
+                "crossed_out" => Modifier::CROSSED_OUT,
+                _ => return Err(format!("unknown modifier `{modifier}`")),
+            };
+            style = style.add_modifier(modifier);
+        }
+        Ok(style)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ratatui::style::Modifier;
+
+    use super::Theme;
+
+    #[test]
+    fn test_without_colors() {
+        let theme = Theme::default().without_colors();
+        assert_eq!((theme.matched.fg, theme.matched.bg), (None, None));
+        assert!(theme
+            .matched
+            .add_modifier
+            .contains(Modifier::BOLD | Modifier::UNDERLINED));
+        assert!(theme.status.add_modifier.contains(Modifier::REVERSED));
+        assert!(theme.selected.add_modifier.contains(Modifier::REVERSED));
+    }
+}
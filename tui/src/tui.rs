@@ -1,20 +1,38 @@
 use std::io::{stdout, Write};
+use std::path::PathBuf;
 
-use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
-use ratatui::layout::{Constraint, Flex, Layout, Position, Rect};
-use ratatui::style::{Color, Style, Stylize};
-use ratatui::text::Line;
-use ratatui::widgets::{Block, Borders, Padding, Paragraph};
-use ratatui::{init, restore, DefaultTerminal, Frame};
-use redo::todo::TodoListCollection;
-use redo::TodoList;
+use crossterm::event::{
+    DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+};
+use crossterm::execute;
+use crossterm::terminal::{enable_raw_mode, EnterAlternateScreen};
+use ratatui::backend::CrosstermBackend;
+use ratatui::layout::{Constraint, Layout, Position, Rect};
+use ratatui::{restore, DefaultTerminal, Terminal};
+use redo::sort::SortKey;
+use redo::todo::{list_name, Stats, TodoListCollection};
+use redo::{export, parser, TodoList};
 
+use crate::command::{Command, CommandLine, CommandLineState, Message};
+use crate::config::Config;
 use crate::editor::{Editor, EditorState};
 use crate::event::EventHandler;
+use crate::external::{stamp_changes, EditTarget, ExternalEdit};
+use crate::finder::{Finder, FinderState};
+use crate::help::{HelpScreen, HelpState};
+use crate::history::History;
+use crate::keymap::{Action, Context, Keymap};
+use crate::modal::{KeySequence, Mode};
+use crate::notes::{Notes, NotesState};
+use crate::recovery::{self, Recovery};
+use crate::search::{Search, SearchState};
 use crate::selection::{SelectionBar, SelectionState};
+use crate::status::{self, Status};
+use crate::tabs::{self, Tab};
+use crate::theme::Theme;
 use crate::viewport::Viewport;
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, PartialEq, Eq)]
 pub enum ScreenState {
     #[default]
     Selection,
@@ -28,47 +46,22 @@ pub enum PopupState {
     Selection,
 }
 
-#[derive(Debug, Default)]
-pub struct HelpScreen {
-    active: bool,
-}
-
-impl HelpScreen {
-    pub fn draw(&self, help_area: Rect, frame: &mut Frame) {
-        let help_vec: Vec<Line> = vec![
-            " Selection Mode "
-                .fg(Color::Rgb(255, 255, 255))
-                .bg(Color::Rgb(183, 72, 101))
-                .bold()
-                .into(),
-            "".into(),
-            "Up/k            Move up   ".into(),
-            "Down/j          Move down  ".into(),
-            "Right/l         Move right ".into(),
-            "Left/h          Move Left ".into(),
-            "Space           Select List".into(),
-            "Enter           Create List".into(),
-            "Esc             Leave Popup".into(),
-        ];
-
-        let [layout] = Layout::vertical([Constraint::Length(help_vec.len() as u16 + 6)])
-            .flex(Flex::Center)
-            .areas(help_area);
-        let [layout] = Layout::horizontal([Constraint::Length(50)])
-            .flex(Flex::Center)
-            .areas(layout);
-        let help = Paragraph::new(help_vec)
-            .block(Block::default().borders(Borders::ALL).padding(Padding::new(5, 5, 2, 2)))
-            .style(Style::default())
-            .blue()
-            .centered();
-        frame.render_widget(help, layout);
-    }
-}
-
 #[derive(Debug)]
 pub struct Interface {
     pub collection: TodoListCollection,
+    /// where the collection is saved
+    pub file: String,
+    /// the collection as it was last read or written
+    saved: TodoListCollection,
+    /// every open file, the one at `tab` is a placeholder while its state is in the fields above
+    tabs: Vec<Tab>,
+    tab: usize,
+    /// the file of the shown tab is never written and its todos can not be changed
+    read_only: bool,
+    /// files opened later are read-only too
+    pub all_read_only: bool,
+    /// edits of the open files left behind by a crash, they are offered back before anything else
+    recovery: Vec<Recovery>,
 
     selected_list: usize,
     screen_size: Viewport,
@@ -80,80 +73,533 @@ pub struct Interface {
 
     popups: Vec<PopupState>,
     help_screen: HelpScreen,
+    history: History,
+    keymap: Keymap,
+    theme: Theme,
+    search: Search,
+    /// how the editor sorts the todos it shows, the file keeps its order
+    view_sort: Vec<SortKey>,
+    /// whether the editor only shows the todos that can be worked on
+    actionable: bool,
+    finder: Finder,
+    command_line: CommandLine,
+    message: Option<Message>,
+    /// the popup with the notes of a todo of the selected list
+    notes: Option<Notes>,
+    /// an edit in `$EDITOR` that could not be read back, it is opened again instead of starting a new one
+    pending_edit: Option<ExternalEdit>,
+    /// the lists shown in the selection bar while searching or when some are archives, `None` shows every list
+    list_rows: Option<Vec<usize>>,
+    /// whether the selection bar shows the archive sections instead of the lists
+    show_archive: bool,
+    /// whether lists with every todo done are left out of the selection bar
+    hide_completed: bool,
 }
 
 impl Interface {
     pub fn handle_selection_bar(&mut self, event: &Event, names: &Vec<String>) {
+        // the collection is only kept for undo when the event can change it, cloning it on every key is slow
+        let before = self.selection_bar.may_change(event).then(|| self.collection.clone());
         if let Some(state) = self.selection_bar.handle_event(event, names) {
             match state {
                 SelectionState::DelPopup => _ = self.popups.pop(),
-                SelectionState::Show(idx) => {
-                    self.selected_list = idx;
+                SelectionState::Show(row) => {
+                    self.selected_list = self.list_index(row);
                 }
                 SelectionState::AddPopup => {
                     self.popups.push(PopupState::Selection);
                 }
-                SelectionState::Selected(idx) => {
+                SelectionState::Selected(row) => {
                     self.change_state(ScreenState::Editor);
-                    self.selected_list = idx;
+                    self.selected_list = self.list_index(row);
                 }
                 SelectionState::AddTodo(title) => {
                     self.collection.push(TodoList::new(title, ""));
                     //self.selection_bar.set_names(self.collection_names());
                     self.popups.pop();
+                    // the new list would be hidden by the search or the archive
+                    self.search.clear();
+                    self.show_archive = false;
                 }
-                SelectionState::Remove(idx) => {
-                    if idx == 0 && self.collection.lists.is_empty() {
+                SelectionState::Remove(row) => {
+                    if row == 0 && self.collection.lists.is_empty() {
                         return;
                     }
-                    self.collection.lists.remove(idx);
-                    self.selected_list = self.selected_list.saturating_sub(1);
+                    self.collection.lists.remove(self.list_index(row));
+                    self.selected_list = self.list_index(self.selection_bar.line());
                 }
+                SelectionState::Undo(count) => return self.undo(count),
+                SelectionState::Redo(count) => return self.redo(count),
+                SelectionState::Search => self.search.start(),
+                SelectionState::NextMatch(count) => self.jump_to_match(count, true),
+                SelectionState::PrevMatch(count) => self.jump_to_match(count, false),
+                SelectionState::ClearSearch => self.search.clear(),
+                SelectionState::Command => self.command_line.open(),
+                SelectionState::External(row) => self.edit_externally(EditTarget::List(self.list_index(row))),
+                SelectionState::NextTab(count) => return self.next_tab(count),
+                SelectionState::ReadOnly => self.message = Some(Message::error(self.read_only_error())),
+                SelectionState::PrevTab(count) => return self.prev_tab(count),
             };
         };
+        match before {
+            Some(before) => self.history.record(before, &self.collection, false),
+            None => self.history.unchanged(false),
+        }
     }
 
     pub fn handle_editor(&mut self, event: &Event) {
-        let Some(result) = self
+        let before = self.editor.may_change(event).then(|| self.collection.clone());
+        if let Some(result) = self
             .editor
             .handle_event(event, &mut self.collection.lists[self.selected_list])
+        {
+            match result {
+                EditorState::Selected => {
+                    if !self.collection.lists[self.selected_list].is_empty() {
+                        self.change_state(ScreenState::Selection);
+                    }
+                    tracing::info!("Selected: {:?}", self.collection.lists[self.selected_list]);
+                }
+                EditorState::AddPopup => self.popups.push(PopupState::Editor),
+                EditorState::Add(data) => {
+                    let list = &mut self.collection.lists[self.selected_list];
+                    list.push_str(&data);
+                    self.editor.goto(list.len() - 1, list.len());
+                    self.popups.pop();
+                }
+                EditorState::Remove(idx) => {
+                    let list = &mut self.collection.lists[self.selected_list];
+                    assert!(list.data.len() > idx);
+                    list.data.remove(idx);
+                    self.editor.goto(self.editor.line(), list.len());
+                    if list.is_empty() {
+                        self.change_state(ScreenState::Selection);
+                    }
+                }
+                EditorState::Move(lines, name) => {
+                    self.popups.pop();
+                    self.move_todos(lines, &name);
+                }
+                EditorState::None => self.change_state(ScreenState::Selection),
+                EditorState::DelPopup => _ = self.popups.pop(),
+                EditorState::Undo(count) => return self.undo(count),
+                EditorState::Redo(count) => return self.redo(count),
+                EditorState::Search => self.search.start(),
+                EditorState::NextMatch(count) => self.jump_to_match(count, true),
+                EditorState::PrevMatch(count) => self.jump_to_match(count, false),
+                EditorState::ClearSearch => self.search.clear(),
+                EditorState::Command => self.command_line.open(),
+                EditorState::External(line) => self.edit_externally(EditTarget::Todo(self.selected_list, line)),
+                EditorState::NextTab(count) => return self.next_tab(count),
+                EditorState::ReadOnly => self.message = Some(Message::error(self.read_only_error())),
+                EditorState::PrevTab(count) => return self.prev_tab(count),
+                EditorState::Notes(line) => {
+                    let note = &self.collection.lists[self.selected_list].data[line].note;
+                    match self.read_only && note.is_empty() {
+                        true => self.message = Some(Message::info("no notes")),
+                        false => self.notes = Some(Notes::new(line, note, self.read_only)),
+                    }
+                }
+            };
+        }
+        match before {
+            Some(before) => self.history.record(before, &self.collection, self.editor.is_typing()),
+            None => self.history.unchanged(self.editor.is_typing()),
+        }
+    }
+
+    /// clicking picks a list or a todo, clicking the box of a todo toggles it and the wheel scrolls the pane under
+    /// the mouse
+    fn handle_mouse(&mut self, mouse: MouseEvent) {
+        let screen = Rect::new(0, 0, self.screen_size.x(), self.screen_size.y());
+        let [_, selection_area, editor_area, _] = areas(screen, self.tab_bar_height());
+        let position = Position::new(mouse.column, mouse.row);
+        let lists = self.collection_names().len();
+        let in_selection = selection_area.contains(position);
+        // while picking a list the editor shows the search results instead of the todos
+        let in_editor =
+            editor_area.contains(position) && !(self.screen_state == ScreenState::Selection && self.search.is_active());
+
+        match mouse.kind {
+            MouseEventKind::Down(MouseButton::Left) if in_selection => {
+                self.message = None;
+                if let Some(row) = self.selection_bar.row_at(selection_area, position, lists) {
+                    self.selection_bar.goto(row);
+                    self.focus_selection();
+                }
+            }
+            MouseEventKind::Down(MouseButton::Left) if in_editor => {
+                self.message = None;
+                let before = self.collection.clone();
+                let Some(list) = self.collection.lists.get_mut(self.selected_list) else {
+                    return;
+                };
+                let Some((row, column)) = self.editor.row_at(editor_area, position, list.len()) else {
+                    return;
+                };
+                self.screen_state = ScreenState::Editor;
+                if let Some(EditorState::ReadOnly) = self.editor.click(row, column, list) {
+                    self.message = Some(Message::error(self.read_only_error()));
+                }
+                self.history.record(before, &self.collection, false);
+            }
+            MouseEventKind::ScrollDown | MouseEventKind::ScrollUp => {
+                let amount = match mouse.kind {
+                    MouseEventKind::ScrollDown => SCROLL_LINES,
+                    _ => -SCROLL_LINES,
+                };
+                if in_selection && lists > 0 {
+                    self.selection_bar.scroll_by(amount, lists);
+                    self.focus_selection();
+                } else if in_editor {
+                    if let Some(list) = self.collection.lists.get(self.selected_list) {
+                        self.editor.scroll_by(amount, list.len());
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// picks the list under the cursor of the selection bar, leaving the editor
+    fn focus_selection(&mut self) {
+        self.selected_list = self.list_index(self.selection_bar.line());
+        self.editor.keys.set_mode(Mode::Normal);
+        self.change_state(ScreenState::Selection);
+    }
+
+    /// replaces the notes of the todo at `todo` of the selected list as one change
+    fn set_note(&mut self, todo: usize, note: String) {
+        let before = self.collection.clone();
+        let Some(todo) = self
+            .collection
+            .lists
+            .get_mut(self.selected_list)
+            .and_then(|list| list.data.get_mut(todo))
         else {
             return;
         };
-        match result {
-            EditorState::Selected => {
-                if !self.collection.lists[self.selected_list].is_empty() {
-                    self.change_state(ScreenState::Selection);
+        if todo.note != note {
+            todo.note = note;
+            todo.touch();
+        }
+        self.history.record(before, &self.collection, false);
+    }
+
+    /// gives the terminal to `$EDITOR` to edit a todo or a list, a file that can not be read back is kept so the
+    /// edits are not lost
+    fn edit_externally(&mut self, target: EditTarget) {
+        // an edit that could not be read back is only opened again for the same target, as long as it did not change
+        // since, the indices of the target would point at something else otherwise
+        let pending = self.pending_edit.take().filter(|edit| {
+            edit.target == target && self.edit_content(target).is_ok_and(|content| content == edit.original)
+        });
+        let edit = match pending {
+            Some(edit) => edit,
+            None => match self
+                .edit_content(target)
+                .and_then(|content| ExternalEdit::new(target, &content))
+            {
+                Ok(edit) => edit,
+                Err(e) => {
+                    self.message = Some(Message::error(e));
+                    return;
                 }
-                tracing::info!("Selected: {:?}", self.collection.lists[self.selected_list]);
+            },
+        };
+
+        self.deinit();
+        let ran = edit.run();
+        self.terminal = init_terminal();
+        _ = self.terminal.clear();
+
+        match ran
+            .and_then(|_| edit.read())
+            .and_then(|content| self.apply_edit(edit.target, &content))
+        {
+            Ok(text) => {
+                edit.remove();
+                self.message = Some(Message::info(text));
+            }
+            Err(e) => {
+                let path = edit.path.display();
+                self.message = Some(Message::error(format!("{e}, E opens the edit in {path} again")));
+                self.pending_edit = Some(edit);
+            }
+        }
+        self.clamp_cursors();
+    }
+
+    /// what the editor starts with, the todo without its list or the whole list
+    fn edit_content(&self, target: EditTarget) -> Result<String, String> {
+        let gone = || "there is nothing to edit".to_string();
+        match target {
+            EditTarget::Todo(list, todo) => {
+                let todo = self
+                    .collection
+                    .lists
+                    .get(list)
+                    .and_then(|list| list.data.get(todo))
+                    .ok_or_else(gone)?;
+                Ok(parser::serialize_todos(std::slice::from_ref(todo)))
+            }
+            EditTarget::List(list) => {
+                let list = self.collection.lists.get(list).ok_or_else(gone)?;
+                Ok(format!("{}:\n{}", list.title, parser::serialize_todos(&list.data)))
             }
-            EditorState::AddPopup => self.popups.push(PopupState::Editor),
-            EditorState::Add(data) => {
-                if !self.collection.lists[self.selected_list].is_empty() {
-                    self.editor.cursor.y += 1;
+        }
+    }
+
+    /// replaces the todo or the list with what was read back from the editor
+    fn apply_edit(&mut self, target: EditTarget, content: &str) -> Result<String, String> {
+        if content.trim().is_empty() {
+            return Ok("the edit was empty, nothing changed".to_string());
+        }
+        let gone = || "what was edited is gone".to_string();
+        match target {
+            EditTarget::Todo(list, todo) => {
+                let title = &self.collection.lists.get(list).ok_or_else(gone)?.title;
+                let mut edited = parser::parse_strict(&format!("{title}:\n{content}"))?;
+                if edited.lists.len() > 1 {
+                    return Err("lists can not be added while editing a todo".to_string());
+                }
+                let list = &mut self.collection.lists[list];
+                let before = list.data.get(todo).cloned().ok_or_else(gone)?;
+                let mut todos = edited.lists.remove(0).data;
+                stamp_changes(&mut todos, &[before]);
+                let count = todos.len();
+                list.data.splice(todo..=todo, todos);
+                match count {
+                    1 => Ok("edited the todo".to_string()),
+                    count => Ok(format!("edited the todo into {count} todos")),
                 }
-                self.collection.lists[self.selected_list].push_str(&data);
             }
-            EditorState::Remove(idx) => {
-                let list = &mut self.collection.lists[self.selected_list];
-                assert!(list.data.len() > idx);
-                list.data.remove(idx);
-                if list.data.len().saturating_sub(1) < self.editor.cursor.y as usize {
-                    self.editor.cursor.y = self.editor.cursor.y.saturating_sub(1);
+            EditTarget::List(list) => {
+                let mut edited = parser::parse_strict(content)?;
+                if edited.lists.len() != 1 {
+                    return Err(format!("expected one list, found {}", edited.lists.len()));
                 }
-                if list.is_empty() {
-                    self.change_state(ScreenState::Selection);
+                let mut edited = edited.lists.remove(0);
+                if self.collection.find(edited.name()).is_some_and(|idx| idx != list) {
+                    return Err(format!("there already is a list called {}", edited.title));
                 }
+                let list = self.collection.lists.get_mut(list).ok_or_else(gone)?;
+                stamp_changes(&mut edited.data, &list.data);
+                *list = edited;
+                Ok(format!("edited {}", list.title))
+            }
+        }
+    }
+
+    /// moves todos of the selected list to the list called `name`, creating it if it does not exist
+    fn move_todos(&mut self, lines: Vec<usize>, name: &str) {
+        let name = name.trim();
+        if name.is_empty() {
+            return;
+        }
+        let target = match self.collection.find(name) {
+            Some(target) => target,
+            None => {
+                self.collection.push(TodoList::new(format!("[{name}]"), ""));
+                self.collection.lists.len() - 1
             }
-            EditorState::None => self.change_state(ScreenState::Selection),
-            EditorState::DelPopup => _ = self.popups.pop(),
         };
+        if target == self.selected_list {
+            return;
+        }
+
+        let list = &mut self.collection.lists[self.selected_list];
+        let mut todos = vec![];
+        for line in lines.into_iter().rev() {
+            todos.insert(0, list.data.remove(line));
+        }
+        self.editor.goto(self.editor.line(), list.len());
+        if list.is_empty() {
+            self.change_state(ScreenState::Selection);
+        }
+        self.collection.lists[target].data.extend(todos);
+    }
+
+    /// moves to the `count`th match after (or before) the cursor, wrapping around the end of the collection
+    fn jump_to_match(&mut self, count: u16, forward: bool) {
+        let results = self.search.results(&self.collection);
+        if results.is_empty() {
+            return;
+        }
+        // in the selection bar the cursor is before the first todo of the list
+        let mut current = match self.screen_state {
+            ScreenState::Editor => (self.selected_list, Some(self.editor.line())),
+            _ => (self.selected_list, None),
+        };
+        for _ in 0..count {
+            let next = match forward {
+                true => results.iter().find(|(list, todo)| (*list, Some(*todo)) > current),
+                false => results.iter().rev().find(|(list, todo)| (*list, Some(*todo)) < current),
+            };
+            let wrapped = match forward {
+                true => results.first(),
+                false => results.last(),
+            };
+            let (list, todo) = next.or(wrapped).copied().unwrap_or_default();
+            current = (list, Some(todo));
+        }
+
+        let (list, todo) = current;
+        self.jump_to(list, todo);
     }
+
+    /// shows the list, or the todo in it, clearing the search if it would hide it
+    fn jump_to(&mut self, list: usize, todo: Option<usize>) {
+        let Some(todo_list) = self.collection.lists.get(list) else {
+            return;
+        };
+        let hidden = match todo {
+            Some(todo) => !self.search.matches(todo_list, &todo_list.data[todo]),
+            None => self.list_rows.as_ref().is_some_and(|rows| !rows.contains(&list)),
+        };
+        if self.search.is_active() && hidden {
+            self.search.clear();
+        }
+        self.show_archive = todo_list.is_archive();
+
+        self.selected_list = list;
+        self.popups.clear();
+        self.editor.reset();
+        self.selection_bar.reset();
+        let state = match todo {
+            Some(_) => ScreenState::Editor,
+            None => ScreenState::Selection,
+        };
+        self.change_state(state);
+        self.refresh_view();
+        let len = self.collection.lists[list].len();
+        self.editor.goto(todo.unwrap_or_default(), len);
+    }
+
+    /// filters the selection bar and the editor by the search and sorts the editor, keeping the cursors on what is
+    /// shown
+    fn refresh_view(&mut self) {
+        let lists = &self.collection.lists;
+        // every archived todo is done, so hiding completed lists only applies to the lists
+        let hide_completed = self.hide_completed && !self.show_archive;
+        let shown = |idx: &usize| {
+            let list = &lists[*idx];
+            list.is_archive() == self.show_archive && !(hide_completed && list.stats().is_complete())
+        };
+        self.list_rows = match self.search.list_rows(&self.collection) {
+            Some(rows) => Some(rows.into_iter().filter(shown).collect()),
+            None if hide_completed || self.show_archive || lists.iter().any(TodoList::is_archive) => {
+                Some((0..lists.len()).filter(shown).collect())
+            }
+            None => None,
+        };
+        if let Some(rows) = &mut self.list_rows {
+            // the list being edited stays around even when nothing in it matches anymore
+            if self.screen_state == ScreenState::Editor && !rows.contains(&self.selected_list) {
+                let row = rows.partition_point(|idx| *idx < self.selected_list);
+                rows.insert(row, self.selected_list);
+            }
+        }
+        let row = match &self.list_rows {
+            Some(rows) => match rows.iter().position(|idx| *idx == self.selected_list) {
+                Some(row) => row,
+                None => {
+                    self.selected_list = rows.first().copied().unwrap_or(self.selected_list);
+                    0
+                }
+            },
+            None => self.selected_list,
+        };
+        self.selection_bar.goto(row);
+
+        let Some(list) = self.collection.lists.get(self.selected_list) else {
+            return;
+        };
+        let dependencies = self.collection.dependencies();
+        let rows = match (self.search.is_active(), self.view_sort.is_empty(), self.actionable) {
+            (false, true, false) => None,
+            (true, true, false) => self.search.rows(list),
+            _ => Some(
+                list.sorted(&self.view_sort)
+                    .into_iter()
+                    .filter(|idx| {
+                        let todo = &list.data[*idx];
+                        self.search.matches(list, todo) && (!self.actionable || dependencies.is_actionable(todo))
+                    })
+                    .collect(),
+            ),
+        };
+        self.editor.set_rows(rows, list.len());
+    }
+
+    /// the index into the collection of the list shown at `row` of the selection bar
+    fn list_index(&self, row: usize) -> usize {
+        match &self.list_rows {
+            Some(rows) => rows.get(row).copied().unwrap_or(self.collection.lists.len()),
+            None => row,
+        }
+    }
+
+    fn undo(&mut self, count: u16) {
+        if self.history.undo(&mut self.collection, count) {
+            self.clamp_cursors();
+        }
+    }
+
+    fn redo(&mut self, count: u16) {
+        if self.history.redo(&mut self.collection, count) {
+            self.clamp_cursors();
+        }
+    }
+
+    /// keeps the selected list and the cursors inside the collection after it was replaced
+    fn clamp_cursors(&mut self) {
+        let lists = self.collection.lists.len();
+        self.selected_list = usize::min(self.selected_list, lists.saturating_sub(1));
+        self.selection_bar.goto(self.selected_list);
+        match self.collection.lists.get(self.selected_list) {
+            Some(list) if !list.is_empty() => self.editor.goto(self.editor.line(), list.len()),
+            _ => self.change_state(ScreenState::Selection),
+        }
+    }
+}
+
+fn sort_names(keys: &[SortKey]) -> String {
+    keys.iter().map(|key| key.name()).collect::<Vec<_>>().join(", ")
+}
+
+/// how many lines a turn of the mouse wheel scrolls
+const SCROLL_LINES: i32 = 3;
+
+/// takes over the terminal, with the mouse reported as events. Unlike `ratatui::init` no panic hook is installed,
+/// since this runs again after every `$EDITOR` and the hook of the app already gives the terminal back
+pub fn init_terminal() -> DefaultTerminal {
+    _ = enable_raw_mode();
+    _ = execute!(stdout(), EnterAlternateScreen, EnableMouseCapture);
+    Terminal::new(CrosstermBackend::new(stdout())).expect("could not take over the terminal")
+}
+
+pub fn restore_terminal() {
+    _ = execute!(stdout(), DisableMouseCapture);
+    restore();
+}
+
+/// splits the screen into the tab bar, the selection bar, the editor and the status bar
+fn areas(screen: Rect, tab_bar_height: u16) -> [Rect; 4] {
+    let [tab_area, main_area, status_area] = Layout::vertical([
+        Constraint::Length(tab_bar_height),
+        Constraint::Fill(1),
+        Constraint::Length(1),
+    ])
+    .areas(screen);
+    let [selection_area, editor_area] =
+        Layout::horizontal([Constraint::Percentage(20), Constraint::Percentage(80)]).areas(main_area);
+    [tab_area, selection_area, editor_area, status_area]
 }
 
 impl Default for Interface {
     fn default() -> Self {
-        Self::new(TodoListCollection::default())
+        Self::new(vec![Tab::default()], Config::default())
     }
 }
 
@@ -164,143 +610,825 @@ pub enum InterfaceState {
 impl EventHandler<(), InterfaceState> for Interface {
     fn handle_event(&mut self, event: &Event, _: ()) -> Option<InterfaceState> {
         self.handle_resize(event);
-        if self.should_quit(event) {
-            return Some(InterfaceState::Quit(Ok(())));
+
+        if !self.recovery.is_empty() {
+            if let Event::Key(key) = event {
+                match key.code {
+                    KeyCode::Char('y') => self.recover(true),
+                    KeyCode::Char('n') => self.recover(false),
+                    _ if self.keymap.global(key) == Some(Action::Quit) => return Some(InterfaceState::Quit(Ok(()))),
+                    _ => {}
+                }
+            }
+            return None;
         }
 
         if let Event::Key(key) = event {
-            match key.code {
-                KeyCode::Esc => {
-                    if self.help_screen.active {
-                        self.help_screen.active = false;
-                        self.change_state(ScreenState::Selection);
+            self.message = None;
+            // plain characters are text while typing, only chords are global then
+            let chord = key.modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT);
+            let action = match self.is_typing() && !chord {
+                true => None,
+                false => self.keymap.global(key),
+            };
+            match action {
+                Some(Action::Quit) => return Some(InterfaceState::Quit(Ok(()))),
+                Some(Action::Help) => {
+                    match self.help_screen.active {
+                        true => self.close_help(),
+                        false => self.open_help(),
                     }
+                    return None;
                 }
-                KeyCode::Char('?') => {
-                    self.help_screen.active = !self.help_screen.active;
-                    if self.help_screen.active {
-                        self.change_state(ScreenState::Help);
-                    } else {
-                        self.change_state(ScreenState::Selection);
+                Some(Action::Finder) if !self.help_screen.active => {
+                    match self.finder.active {
+                        true => self.finder.active = false,
+                        false => self.finder.open(&self.collection),
                     }
+                    return None;
                 }
-
                 _ => {}
-            };
+            }
+        }
+
+        if let Some(notes) = &mut self.notes {
+            match notes.handle_event(event, ()) {
+                Some(NotesState::Save(note)) => {
+                    let todo = notes.todo;
+                    self.notes = None;
+                    self.set_note(todo, note);
+                }
+                Some(NotesState::Close) => self.notes = None,
+                None => {}
+            }
+            return None;
+        }
+
+        if self.command_line.active {
+            if let Some(CommandLineState::Run(line)) = self.command_line.handle_event(event, ()) {
+                return self.run_command(&line);
+            }
+            return None;
+        }
+
+        if self.finder.active {
+            match self.finder.handle_event(event, &self.collection) {
+                Some(FinderState::Close) => self.finder.active = false,
+                Some(FinderState::Jump(list, todo)) => {
+                    self.finder.active = false;
+                    self.jump_to(list, todo);
+                }
+                None => {}
+            }
+            return None;
+        }
+
+        if self.search.editing {
+            if let Some(state) = self.search.handle_event(event, ()) {
+                match state {
+                    SearchState::Changed | SearchState::Cancelled => self.refresh_view(),
+                    SearchState::Confirmed => {
+                        self.refresh_view();
+                        // like vim the search jumps to the first match after the cursor
+                        if self.screen_state == ScreenState::Editor {
+                            self.jump_to_match(1, true);
+                        }
+                    }
+                }
+            }
+            return None;
         }
 
         match self.screen_state {
+            // the mouse only works on the panes, popups and typed text keep it out
+            ScreenState::Selection | ScreenState::Editor if matches!(event, Event::Mouse(_)) => {
+                if let Event::Mouse(mouse) = event {
+                    if self.popups.is_empty() && !self.is_typing() {
+                        self.handle_mouse(*mouse);
+                    }
+                }
+            }
             ScreenState::Selection => {
                 let names = self.collection_names().clone();
                 self.handle_selection_bar(event, &names);
             }
             ScreenState::Editor => self.handle_editor(event),
             ScreenState::Help => {
-                if let Event::Key(key) = event {
-                    if key.code == KeyCode::Esc {
-                        self.change_state(ScreenState::Selection);
-                    }
+                if let Some(HelpState::Close) = self.help_screen.handle_event(event, ()) {
+                    self.close_help();
                 }
             }
         };
 
+        // edits can make todos match or stop matching, but not while they are still being typed
+        if !self.is_typing() {
+            self.refresh_view();
+        }
+
         None
     }
 }
 
 impl Interface {
-    pub fn new(collection: TodoListCollection) -> Self {
-        let terminal = init();
+    /// shows the first of `tabs`, there has to be at least one
+    pub fn new(mut tabs: Vec<Tab>, config: Config) -> Self {
+        let Config {
+            keymap,
+            theme,
+            sort,
+            hide_completed,
+        } = config;
+        let Tab {
+            file,
+            collection,
+            saved,
+            history,
+            selected_list,
+            read_only,
+        } = std::mem::take(&mut tabs[0]);
+        let terminal = init_terminal();
         let screen_size = ratatui::Terminal::size(&terminal).unwrap_or_default();
         let viewport = Viewport::new(screen_size.height, screen_size.width);
+        // broken links are pointed out right away, they would quietly stop blocking todos otherwise
+        let message = collection.dependencies().validate().err().map(Message::error);
         let mut editor = Editor::default();
-        editor.viewport = viewport;
+        editor.keys = KeySequence::new(keymap.clone());
         let mut selection_bar = SelectionBar::default();
-        selection_bar.viewport = viewport;
+        selection_bar.keys = KeySequence::new(keymap.clone());
 
-        Self {
+        let mut interface = Self {
             popups: vec![],
             terminal,
             collection,
+            file,
+            saved,
+            tabs,
+            tab: 0,
+            read_only,
+            all_read_only: false,
+            recovery: vec![],
             editor,
             selection_bar,
             help_screen: HelpScreen::default(),
+            history,
+            keymap,
+            theme,
+            search: Search::default(),
+            view_sort: sort,
+            actionable: false,
+            finder: Finder::default(),
+            command_line: CommandLine::default(),
+            message,
+            notes: None,
+            pending_edit: None,
+            list_rows: None,
+            show_archive: false,
+            hide_completed,
 
-            selected_list: 0,
+            selected_list,
             screen_size: viewport,
             screen_state: ScreenState::default(),
-        }
+        };
+        interface.resize_panes();
+        interface.lock_panes();
+        interface.refresh_view();
+        interface
     }
 
     pub fn draw(&mut self) {
         let names = self.collection_names();
+        let stats = self.list_stats();
+
+        let tab_names = self.tab_names();
+        let tab_bar_height = self.tab_bar_height();
+        let mut status = self.status(names.len());
+        status.message = self.recovery_prompt().or_else(|| self.message.clone());
 
         _ = self.terminal.draw(|frame| {
-            let layout = Layout::horizontal([Constraint::Percentage(20), Constraint::Percentage(80)]);
-            let [selection_area, editor_area] = layout.areas(frame.area());
+            let [tab_area, selection_area, editor_area, status_area] = areas(frame.area(), tab_bar_height);
 
             match self.screen_state {
                 ScreenState::Selection => {
                     // have to do x+1, else it puts the cursor at | instead of [
                     let (y, x) = self.selection_bar.cursor_pos();
-                    let position = Position::new(x + 1, y + 1);
+                    let position = Position::new(selection_area.x + x + 1, selection_area.y + y + 1);
                     frame.set_cursor_position(position);
                 }
 
                 ScreenState::Editor => {
                     let padding: u16 = 4; // padding is `[ ] `
                     let (y, x) = self.editor.cursor_pos();
-                    let position = Position::new(editor_area.x + x + 1 + padding, y + 1);
+                    // todos wider than the editor are cut off, the cursor stays at the border then
+                    let x = u16::min(x + padding, self.editor.width().saturating_sub(1));
+                    let position = Position::new(editor_area.x + x + 1, editor_area.y + y + 1);
                     frame.set_cursor_position(position);
                 }
                 ScreenState::Help => {
-                    self.help_screen.draw(frame.area(), frame);
+                    self.help_screen.draw(frame.area(), frame, &self.keymap, &self.theme);
                     return;
                 }
             };
 
+            if tab_area.height > 0 {
+                let tabs = tab_names
+                    .iter()
+                    .map(|(name, dirty)| (name.as_str(), *dirty))
+                    .collect::<Vec<_>>();
+                tabs::draw(frame, tab_area, &tabs, self.tab, &self.theme);
+            }
+
             let list = self.collection.lists.get(self.selected_list);
-            self.selection_bar.draw(frame, selection_area, &names);
-            self.editor.draw(frame, editor_area, list);
+            self.selection_bar
+                .draw(frame, selection_area, &names, &stats, &self.search, &self.theme);
+            match self.screen_state {
+                // while picking a list every match in the collection is shown
+                ScreenState::Selection if self.search.is_active() => {
+                    self.search
+                        .draw_results(frame, editor_area, &self.collection, &self.theme)
+                }
+                _ => self.editor.draw(
+                    frame,
+                    editor_area,
+                    list,
+                    &self.collection.dependencies(),
+                    &self.search,
+                    &self.theme,
+                ),
+            }
+
+            status::draw(frame, status_area, &status, &self.theme);
 
             if let Some(popup) = self.popups.last() {
                 match popup {
-                    PopupState::Editor => self.editor.draw_popup(frame),
-                    PopupState::Selection => self.selection_bar.draw_popup(frame),
+                    PopupState::Editor => self.editor.draw_popup(frame, &self.theme),
+                    PopupState::Selection => self.selection_bar.draw_popup(frame, &self.theme),
+                }
+            }
+
+            if let Some(notes) = &self.notes {
+                if let Some(todo) = list.and_then(|list| list.data.get(notes.todo)) {
+                    notes.draw(frame, frame.area(), todo, &self.theme);
                 }
             }
+
+            if self.finder.active {
+                self.finder.draw(frame, frame.area(), &self.collection, &self.theme);
+            }
+
+            if self.search.editing {
+                self.search.draw_prompt(frame, frame.area());
+                let area = Search::prompt_area(frame.area());
+                let x = area.x + 1 + self.search.query.chars().count() as u16;
+                frame.set_cursor_position(Position::new(x, area.y));
+            }
+
+            if self.command_line.active {
+                self.command_line.draw(frame, frame.area(), &self.theme);
+            }
         });
     }
 
-    pub fn add_todo(&mut self, content: &str) {
-        let list = self.collection.get_mut_todo_list(self.selected_list);
-        if let Some(list) = list {
-            list.push_str(content)
+    pub fn deinit(&self) {
+        restore_terminal();
+    }
+
+    /// writes the collection to its file
+    pub fn save(&mut self) -> Result<(), String> {
+        if self.read_only {
+            return Err(self.read_only_error());
         }
+        tabs::write(&self.file, &self.collection)?;
+        self.saved = self.collection.clone();
+        Ok(())
     }
 
-    pub fn remove_todo(&mut self) {
-        let list = self.collection.get_mut_todo_list(self.selected_list);
-        if let Some(list) = list {
-            list.data.remove(self.editor.cursor.x as usize);
+    /// writes the unsaved edits of every tab to its recovery file, for when the app can not quit normally
+    pub fn emergency_save(&self) -> Vec<Result<PathBuf, String>> {
+        let mut written = vec![];
+        for (idx, tab) in self.tabs.iter().enumerate() {
+            let (file, collection, dirty, read_only) = match idx == self.tab {
+                true => (
+                    &self.file,
+                    &self.collection,
+                    self.collection != self.saved,
+                    self.read_only,
+                ),
+                false => (&tab.file, &tab.collection, tab.is_dirty(), tab.read_only),
+            };
+            if dirty && !read_only {
+                written.push(recovery::write(file, collection));
+            }
         }
+        written
     }
 
-    pub fn deinit(&self) {
-        restore();
+    /// looks for edits of the open files that a crash left behind, read-only files are left alone
+    pub fn find_recovery(&mut self) {
+        self.recovery = (0..self.tabs.len())
+            .filter_map(|idx| match idx == self.tab {
+                true if !self.read_only => recovery::find(idx, &self.file),
+                false if !self.tabs[idx].read_only => recovery::find(idx, &self.tabs[idx].file),
+                _ => None,
+            })
+            .collect();
+    }
+
+    fn recovery_prompt(&self) -> Option<Message> {
+        if self.recovery.is_empty() {
+            return None;
+        }
+        let files = self
+            .recovery
+            .iter()
+            .map(|recovery| match recovery.tab == self.tab {
+                true => tabs::name(&self.file),
+                false => tabs::name(&self.tabs[recovery.tab].file),
+            })
+            .collect::<Vec<_>>();
+        Some(Message::error(format!(
+            "{} has edits from a crash that were not saved, y recovers them, n throws them away",
+            files.join(", ")
+        )))
+    }
+
+    /// puts the edits from a crash in place of the files, as a change that can be undone, or throws them away
+    fn recover(&mut self, keep: bool) {
+        for found in std::mem::take(&mut self.recovery) {
+            recovery::remove(&found.path);
+            if !keep {
+                continue;
+            }
+            let (collection, history) = match found.tab == self.tab {
+                true => (&mut self.collection, &mut self.history),
+                false => {
+                    let tab = &mut self.tabs[found.tab];
+                    (&mut tab.collection, &mut tab.history)
+                }
+            };
+            let before = std::mem::replace(collection, found.collection);
+            history.record(before, collection, false);
+        }
+        self.clamp_cursors();
+        self.refresh_view();
+        self.message = Some(match keep {
+            true => Message::info("recovered the edits, they are written with the file"),
+            false => Message::info("threw the edits away"),
+        });
+    }
+
+    /// writes every tab with unsaved changes to its file
+    pub fn save_all(&mut self) -> Result<(), String> {
+        let mut errors = vec![];
+        if !self.read_only && self.collection != self.saved {
+            if let Err(e) = self.save() {
+                errors.push(e);
+            }
+        }
+        for (idx, tab) in self.tabs.iter_mut().enumerate() {
+            if idx == self.tab || tab.read_only || !tab.is_dirty() {
+                continue;
+            }
+            if let Err(e) = tab.save() {
+                errors.push(e);
+            }
+        }
+        match errors.is_empty() {
+            true => Ok(()),
+            false => Err(errors.join(", ")),
+        }
+    }
+
+    /// puts every tab back the way it was last read or written, so quitting leaves the files alone
+    fn discard_all(&mut self) {
+        self.collection = self.saved.clone();
+        for tab in &mut self.tabs {
+            tab.collection = tab.saved.clone();
+        }
+    }
+
+    fn run_command(&mut self, line: &str) -> Option<InterfaceState> {
+        if line.trim().is_empty() {
+            return None;
+        }
+        let command = match line.parse::<Command>() {
+            Ok(command) => command,
+            Err(e) => {
+                self.message = Some(Message::error(e));
+                return None;
+            }
+        };
+
+        if self.read_only && command.edits() {
+            self.message = Some(Message::error(self.read_only_error()));
+            return None;
+        }
+
+        let before = self.collection.clone();
+        let result = match command {
+            Command::Quit(false) if self.tab_names().iter().any(|(_, dirty)| *dirty) => {
+                Err("unsaved changes (add ! to override)".to_string())
+            }
+            Command::Quit(force) => {
+                if force {
+                    self.discard_all();
+                }
+                return Some(InterfaceState::Quit(Ok(())));
+            }
+            Command::WriteQuit => match self.save() {
+                Ok(()) => return Some(InterfaceState::Quit(Ok(()))),
+                Err(e) => Err(e),
+            },
+            Command::Write(None) => self.save().map(|_| format!("written {}", self.file)),
+            Command::Write(Some(file)) => {
+                // like vim a collection without a file takes the name it is first written to, once that worked
+                match self.file.is_empty() || file == self.file {
+                    true => {
+                        let previous = std::mem::replace(&mut self.file, file.clone());
+                        let saved = self.save();
+                        if saved.is_err() {
+                            self.file = previous;
+                        }
+                        saved
+                    }
+                    false => tabs::write(&file, &self.collection),
+                }
+                .map(|_| format!("written {file}"))
+            }
+            Command::Sort(keys) => match self.collection.lists.get_mut(self.selected_list) {
+                Some(list) => {
+                    list.sort_by_keys(&keys);
+                    Ok(format!("sorted {}", list.title))
+                }
+                None => Err("there is no list to sort".to_string()),
+            },
+            Command::View(keys) => {
+                self.view_sort = keys;
+                self.refresh_view();
+                match self.view_sort.is_empty() {
+                    true => Ok("showing the order of the file".to_string()),
+                    false => Ok(format!("showing sorted by {}", sort_names(&self.view_sort))),
+                }
+            }
+            Command::Actionable => {
+                self.actionable = !self.actionable;
+                self.refresh_view();
+                match (self.collection.dependencies().validate(), self.actionable) {
+                    (Err(e), _) => Err(e),
+                    (Ok(()), true) => Ok("showing only actionable todos".to_string()),
+                    (Ok(()), false) => Ok("showing every todo".to_string()),
+                }
+            }
+            Command::Rename(name) => self.rename(&name),
+            Command::Export(format, file) => export::export(&self.collection, format)
+                .and_then(|content| std::fs::write(&file, content).map_err(|e| format!("could not write {file}: {e}")))
+                .map(|_| format!("exported to {file}")),
+            Command::Archive(days) => match self.collection.archive(days) {
+                0 => Ok("nothing to archive".to_string()),
+                moved => Ok(format!("archived {moved} todos, :archived shows them")),
+            },
+            Command::Archived => self.toggle_archive(),
+            Command::Completed => {
+                self.hide_completed = !self.hide_completed;
+                self.refresh_view();
+                match self.hide_completed {
+                    true => Ok("hiding the lists with every todo done".to_string()),
+                    false => Ok("showing every list".to_string()),
+                }
+            }
+            Command::Restore => self.restore(),
+            Command::Open(file) => {
+                let result = self.open(file);
+                self.show(result);
+                return None;
+            }
+            Command::Close => {
+                let result = self.close();
+                self.show(result);
+                return None;
+            }
+        };
+        self.history.record(before, &self.collection, false);
+        self.clamp_cursors();
+        self.refresh_view();
+        self.show(result);
+        None
+    }
+
+    /// switches the selection bar between the lists and their archive sections
+    fn toggle_archive(&mut self) -> Result<String, String> {
+        let archive = !self.show_archive;
+        let Some(first) = self
+            .collection
+            .lists
+            .iter()
+            .position(|list| list.is_archive() == archive)
+        else {
+            return match archive {
+                true => Err("nothing is archived".to_string()),
+                false => Err("there are only archives".to_string()),
+            };
+        };
+        self.show_archive = archive;
+        self.selected_list = first;
+        self.change_state(ScreenState::Selection);
+        match archive {
+            true => Ok("showing the archive, :restore puts todos back".to_string()),
+            false => Ok("showing the lists".to_string()),
+        }
+    }
+
+    /// puts the archived todo under the cursor back into its list, or every todo of the archive when picking a list
+    fn restore(&mut self) -> Result<String, String> {
+        let Some(list) = self
+            .collection
+            .lists
+            .get(self.selected_list)
+            .filter(|list| list.is_archive())
+        else {
+            return Err("not in an archive, :archived shows it".to_string());
+        };
+        let todos = match self.screen_state {
+            ScreenState::Editor => vec![self.editor.line()],
+            _ => (0..list.len()).collect(),
+        };
+        let emptied = todos.len() == list.len();
+        let restored = self.collection.restore(self.selected_list, &todos);
+        // restoring the last todo of an archive removes it
+        if emptied {
+            self.change_state(ScreenState::Selection);
+            self.show_archive = self.collection.lists.iter().any(TodoList::is_archive);
+        }
+        Ok(format!("restored {restored} todos"))
+    }
+
+    fn show(&mut self, result: Result<String, String>) {
+        self.message = Some(match result {
+            Ok(text) => Message::info(text),
+            Err(e) => Message::error(e),
+        });
+    }
+
+    fn rename(&mut self, name: &str) -> Result<String, String> {
+        let name = list_name(name.trim());
+        if name.is_empty() {
+            return Err("a list needs a name".to_string());
+        }
+        if self.collection.find(name).is_some_and(|idx| idx != self.selected_list) {
+            return Err(format!("there already is a list called {name}"));
+        }
+        let Some(list) = self.collection.lists.get_mut(self.selected_list) else {
+            return Err("there is no list to rename".to_string());
+        };
+        list.title = format!("[{name}]");
+        Ok(format!("renamed to {}", list.title))
+    }
+
+    /// starts in the list called `name`
+    pub fn open_list(&mut self, name: &str) {
+        let Some(list) = self.collection.find(name) else {
+            self.message = Some(Message::error(format!("there is no list called {name}")));
+            return;
+        };
+        self.jump_to(list, None);
+        if !self.collection.lists[list].is_empty() {
+            self.change_state(ScreenState::Editor);
+        }
+    }
+
+    /// opens `file` in a new tab, or goes to the tab it is already open in
+    fn open(&mut self, file: String) -> Result<String, String> {
+        if file == self.file {
+            return Ok(format!("{file} is already open"));
+        }
+        if let Some(idx) = self.tabs.iter().position(|tab| tab.file == file) {
+            self.switch_tab(idx);
+            return Ok(format!("switched to {file}"));
+        }
+        let mut tab = Tab::open(file)?;
+        tab.read_only |= self.all_read_only;
+
+        // a tab that was never given a file or anything to save is replaced
+        if self.file.is_empty() && self.collection.lists.is_empty() {
+            self.tabs[self.tab] = tab;
+            self.load_tab(self.tab);
+        } else {
+            self.store_tab();
+            self.tabs.push(tab);
+            self.load_tab(self.tabs.len() - 1);
+            self.resize_panes();
+        }
+        Ok(format!("opened {}", self.file))
+    }
+
+    /// saves the current tab and shows the one after it
+    fn close(&mut self) -> Result<String, String> {
+        if self.tabs.len() == 1 {
+            return Err("this is the last tab, :quit closes it".to_string());
+        }
+        if !self.read_only {
+            self.save()?;
+        }
+        let closed = std::mem::take(&mut self.file);
+        self.tabs.remove(self.tab);
+        self.load_tab(usize::min(self.tab, self.tabs.len() - 1));
+        self.resize_panes();
+        Ok(format!("closed {closed}"))
+    }
+
+    /// goes to the next tab, or to the `count`th tab when there is a count
+    fn next_tab(&mut self, count: Option<u16>) {
+        let len = self.tabs.len();
+        let idx = match count {
+            Some(count) => usize::min(count as usize, len).saturating_sub(1),
+            None => (self.tab + 1) % len,
+        };
+        self.switch_tab(idx);
+    }
+
+    fn prev_tab(&mut self, count: u16) {
+        let len = self.tabs.len();
+        let idx = (self.tab + len - count as usize % len) % len;
+        self.switch_tab(idx);
+    }
+
+    fn switch_tab(&mut self, idx: usize) {
+        if idx == self.tab || idx >= self.tabs.len() {
+            return;
+        }
+        self.store_tab();
+        self.load_tab(idx);
+    }
+
+    /// puts the state of the shown tab back into its place in `tabs`
+    fn store_tab(&mut self) {
+        let tab = &mut self.tabs[self.tab];
+        tab.file = std::mem::take(&mut self.file);
+        tab.collection = std::mem::take(&mut self.collection);
+        tab.saved = std::mem::take(&mut self.saved);
+        tab.history = std::mem::take(&mut self.history);
+        tab.selected_list = self.selected_list;
+        tab.read_only = self.read_only;
+    }
+
+    /// takes the state of the tab at `idx` out of `tabs` and shows it, starting at its list of the last time
+    fn load_tab(&mut self, idx: usize) {
+        let tab = std::mem::take(&mut self.tabs[idx]);
+        self.tab = idx;
+        self.file = tab.file;
+        self.collection = tab.collection;
+        self.saved = tab.saved;
+        self.history = tab.history;
+        self.selected_list = tab.selected_list;
+        self.read_only = tab.read_only;
+        self.lock_panes();
+
+        // what is kept for the edit points into the collection of the other tab
+        self.pending_edit = None;
+        self.notes = None;
+        self.search.clear();
+        self.show_archive = false;
+        self.editor.goto(0, 0);
+        self.change_state(ScreenState::Selection);
+        self.clamp_cursors();
+        self.refresh_view();
+    }
+
+    /// keeps the editor and the selection bar from changing a read-only file
+    fn lock_panes(&mut self) {
+        self.editor.read_only = self.read_only;
+        self.selection_bar.read_only = self.read_only;
     }
 
-    pub fn get_editor_viewport(&self) -> &Viewport {
-        &self.screen_size
+    fn read_only_error(&self) -> String {
+        format!("{} is read-only", tabs::name(&self.file))
     }
 
+    /// the name of every tab and whether it has unsaved changes
+    fn tab_names(&self) -> Vec<(String, bool)> {
+        self.tabs
+            .iter()
+            .enumerate()
+            .map(|(idx, tab)| match idx == self.tab {
+                true => (tabs::name(&self.file).to_string(), self.collection != self.saved),
+                false => (tabs::name(&tab.file).to_string(), tab.is_dirty()),
+            })
+            .collect()
+    }
+
+    /// what the status bar shows, without the message, `lists` is the amount of lists in the selection bar
+    fn status(&self, lists: usize) -> Status {
+        let mode = match self.screen_state {
+            _ if self.notes.is_some() => "NOTES".to_string(),
+            ScreenState::Selection => "LISTS".to_string(),
+            ScreenState::Editor => self.editor.keys.mode().to_string(),
+            ScreenState::Help => "HELP".to_string(),
+        };
+        let mut view = vec![];
+        if !self.view_sort.is_empty() {
+            view.push(format!("sorted by {}", sort_names(&self.view_sort)));
+        }
+        if self.actionable {
+            view.push("actionable".to_string());
+        }
+        if self.show_archive {
+            view.push("archive".to_string());
+        }
+        if self.hide_completed {
+            view.push("done lists hidden".to_string());
+        }
+
+        let list = self.collection.lists.get(self.selected_list);
+        let progress = list.map(|list| {
+            let stats = list.stats();
+            (stats.done, stats.total)
+        });
+        let position = match self.screen_state {
+            ScreenState::Selection if lists > 0 => Some((self.selection_bar.line() + 1, lists)),
+            ScreenState::Editor => list.map(|list| (self.editor.row() + 1, self.editor.row_count(list.len()))),
+            _ => None,
+        };
+
+        Status {
+            mode,
+            file: tabs::name(&self.file).to_string(),
+            dirty: self.collection != self.saved,
+            read_only: self.read_only,
+            view,
+            progress,
+            position,
+            message: None,
+        }
+    }
+
+    /// the tab bar only shows once there is more than one tab
+    fn tab_bar_height(&self) -> u16 {
+        match self.tabs.len() {
+            1 => 0,
+            _ => 1,
+        }
+    }
+
+    /// fits the selection bar and the editor into the screen left between the tab bar and the status bar
+    fn resize_panes(&mut self) {
+        let screen = Rect::new(0, 0, self.screen_size.x(), self.screen_size.y());
+        let [_, selection_area, editor_area, _] = areas(screen, self.tab_bar_height());
+        let lists = self.collection_names().len();
+        self.selection_bar.resize(selection_area, lists);
+        let todos = self.collection.lists.get(self.selected_list).map_or(0, TodoList::len);
+        self.editor.resize(editor_area, todos);
+    }
+
+    /// the titles of the lists shown in the selection bar
     pub fn collection_names(&self) -> Vec<String> {
-        let mut tmp = vec![];
+        self.shown_lists().map(|list| list.title.clone()).collect()
+    }
+
+    /// how far along the lists shown in the selection bar are
+    fn list_stats(&self) -> Vec<Stats> {
+        self.shown_lists().map(TodoList::stats).collect()
+    }
+
+    fn shown_lists(&self) -> impl Iterator<Item = &TodoList> {
         self.collection
             .lists
             .iter()
-            .for_each(|list| tmp.push(list.title.clone()));
-        tmp
+            .enumerate()
+            .filter(|(idx, _)| self.list_rows.as_ref().is_none_or(|rows| rows.contains(idx)))
+            .map(|(_, list)| list)
+    }
+
+    fn open_help(&mut self) {
+        let context = match self.screen_state {
+            ScreenState::Editor if self.editor.keys.mode() == Mode::Visual => Context::Visual,
+            ScreenState::Editor => Context::Editor,
+            _ => Context::Selection,
+        };
+        self.help_screen.open(context, &self.keymap);
+        self.change_state(ScreenState::Help);
+    }
+
+    /// goes back to wherever help was opened from
+    fn close_help(&mut self) {
+        self.help_screen.active = false;
+        match self.help_screen.context {
+            Context::Selection => self.change_state(ScreenState::Selection),
+            Context::Editor | Context::Visual => self.change_state(ScreenState::Editor),
+        }
+    }
+
+    /// whether the focused widget wants keys as text, in which case global bindings are not applied
+    pub fn is_typing(&self) -> bool {
+        if self.search.editing || self.finder.active || self.command_line.active {
+            return true;
+        }
+        if self.notes.as_ref().is_some_and(|notes| !notes.read_only) {
+            return true;
+        }
+        match self.screen_state {
+            ScreenState::Selection => self.selection_bar.is_typing(),
+            ScreenState::Editor => self.editor.is_typing(),
+            ScreenState::Help => false,
+        }
     }
 
     pub fn change_state(&mut self, state: ScreenState) {
@@ -315,17 +1443,10 @@ impl Interface {
         let _ = stdout().flush();
     }
 
-    pub fn should_quit(&mut self, event: &Event) -> bool {
-        let quit_event = KeyEvent::new(KeyCode::Char('q'), KeyModifiers::CONTROL);
-        match event {
-            Event::Key(key) => *key == quit_event,
-            _ => false,
-        }
-    }
-
     pub fn handle_resize(&mut self, event: &Event) {
         if let Event::Resize(x, y) = event {
             self.screen_size = Viewport::new(*y, *x);
+            self.resize_panes();
         }
     }
 }